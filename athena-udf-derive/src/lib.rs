@@ -0,0 +1,250 @@
+//! Derive macros for `athena-udf`.
+//!
+//! Use them through the `derive` feature of `athena-udf`, which re-exports
+//! `FromArrow` and `ToArrow` next to the traits of the same name.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr, Type};
+
+/// A named struct field together with the ROW field name it maps to.
+struct RowField {
+    ident: Ident,
+    name: String,
+    ty: Type,
+}
+
+/// Collects the fields of a plain struct with named fields.
+///
+/// The ROW field name defaults to the Rust field name and can be overridden
+/// with `#[athena_udf(rename = "...")]`.
+fn row_fields(input: &DeriveInput) -> syn::Result<Vec<RowField>> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "ROW types cannot be generic",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "ROW types must be structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "ROW types must be structs with named fields",
+            ))
+        }
+    };
+
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().expect("named field");
+            let mut name = ident.to_string();
+            for attr in field
+                .attrs
+                .iter()
+                .filter(|a| a.path().is_ident("athena_udf"))
+            {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        name = meta.value()?.parse::<LitStr>()?.value();
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported athena_udf attribute"))
+                    }
+                })?;
+            }
+            Ok(RowField {
+                ident,
+                name,
+                ty: field.ty.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Derives `FromArrow` for a struct, reading an Athena `ROW` from an Arrow `StructArray`.
+///
+/// Struct fields are matched to child arrays by name and read with their own
+/// `FromArrow` implementations. A null ROW, or a null in a field that is not an
+/// `Option`, is treated like a null input.
+#[proc_macro_derive(FromArrow, attributes(athena_udf))]
+pub fn derive_from_arrow(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_arrow(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_from_arrow(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let fields = row_fields(input)?;
+    let krate = quote!(::athena_udf);
+
+    let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let names = fields.iter().map(|f| &f.name).collect::<Vec<_>>();
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let positions = (1..=fields.len()).map(syn::Index::from).collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl #krate::FromArrow for #ident {
+            type Column<'a> = (
+                ::std::option::Option<&'a #krate::arrow::buffer::NullBuffer>,
+                #(<#types as #krate::FromArrow>::Column<'a>,)*
+            );
+
+            fn column(
+                array: &dyn #krate::arrow::array::Array,
+            ) -> ::std::result::Result<Self::Column<'_>, #krate::arrow::error::ArrowError> {
+                let array = #krate::nested::struct_column(array)?;
+                ::std::result::Result::Ok((
+                    #krate::arrow::array::Array::nulls(array),
+                    #(<#types as #krate::FromArrow>::column(
+                        #krate::nested::struct_child(array, #names)?,
+                    )?,)*
+                ))
+            }
+
+            fn from_column(
+                column: &Self::Column<'_>,
+                index: usize,
+            ) -> ::std::option::Option<Self> {
+                if column.0.is_some_and(|nulls| nulls.is_null(index)) {
+                    return ::std::option::Option::None;
+                }
+                ::std::option::Option::Some(Self {
+                    #(#idents: <#types as #krate::FromArrow>::from_column(&column.#positions, index)?,)*
+                })
+            }
+
+            fn array_type() -> #krate::arrow::datatypes::DataType {
+                #krate::arrow::datatypes::DataType::Struct(#krate::arrow::datatypes::Fields::from(vec![
+                    #(#krate::arrow::datatypes::Field::new(
+                        #names,
+                        <#types as #krate::FromArrow>::array_type(),
+                        true,
+                    ),)*
+                ]))
+            }
+        }
+    })
+}
+
+/// Derives `ToArrow` for a struct, writing an Athena `ROW` as an Arrow `StructArray`.
+///
+/// Each struct field becomes a child array written with its own `ToArrow`
+/// implementation. Field names, order and child types follow the requested
+/// output type when one is given.
+#[proc_macro_derive(ToArrow, attributes(athena_udf))]
+pub fn derive_to_arrow(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_arrow(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_to_arrow(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let builder = format_ident!("__AthenaUdfRowBuilder");
+    let fields = row_fields(input)?;
+    let krate = quote!(::athena_udf);
+
+    let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let names = fields.iter().map(|f| &f.name).collect::<Vec<_>>();
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let positions = (0..fields.len()).map(syn::Index::from).collect::<Vec<_>>();
+
+    // The builder is declared inside an anonymous const so that it does not
+    // take a name in the user's namespace.
+    Ok(quote! {
+        const _: () = {
+            /// Output builder for the derived `ROW` type, with one child builder per field.
+            #[doc(hidden)]
+            pub struct #builder {
+                validity: ::std::vec::Vec<bool>,
+                data_type: #krate::arrow::datatypes::DataType,
+                fields: (#(<#types as #krate::ToArrow>::Builder,)*),
+            }
+
+            impl #krate::ToArrow for #ident {
+                type ArrayType = #krate::arrow::array::StructArray;
+                type Builder = #builder;
+
+                fn data_type() -> #krate::arrow::datatypes::DataType {
+                    #krate::arrow::datatypes::DataType::Struct(#krate::arrow::datatypes::Fields::from(vec![
+                        #(#krate::arrow::datatypes::Field::new(
+                            #names,
+                            <#types as #krate::ToArrow>::data_type(),
+                            true,
+                        ),)*
+                    ]))
+                }
+
+                fn builder(
+                    capacity: usize,
+                    data_type: ::std::option::Option<&#krate::arrow::datatypes::DataType>,
+                ) -> ::std::result::Result<Self::Builder, #krate::arrow::error::ArrowError> {
+                    ::std::result::Result::Ok(#builder {
+                        validity: ::std::vec::Vec::with_capacity(capacity),
+                        fields: (#(<#types as #krate::ToArrow>::builder(
+                            capacity,
+                            data_type
+                                .map(|data_type| {
+                                    #krate::nested::struct_child_type(
+                                        data_type,
+                                        #names,
+                                        <#types as #krate::ToArrow>::data_type(),
+                                    )
+                                })
+                                .as_ref(),
+                        )?,)*),
+                        data_type: data_type
+                            .cloned()
+                            .unwrap_or_else(<Self as #krate::ToArrow>::data_type),
+                    })
+                }
+            }
+
+            impl #krate::OutputBuilder<#ident> for #builder {
+                fn append_value(
+                    &mut self,
+                    value: #ident,
+                ) -> ::std::result::Result<(), #krate::arrow::error::ArrowError> {
+                    #(#krate::OutputBuilder::append_value(&mut self.fields.#positions, value.#idents)?;)*
+                    self.validity.push(true);
+                    ::std::result::Result::Ok(())
+                }
+
+                fn append_null(&mut self) {
+                    self.validity.push(false);
+                    #(#krate::OutputBuilder::<#types>::append_null(&mut self.fields.#positions);)*
+                }
+
+                fn finish(
+                    self,
+                ) -> ::std::result::Result<#krate::arrow::array::ArrayRef, #krate::arrow::error::ArrowError> {
+                    #krate::nested::build_struct(
+                        &self.data_type,
+                        vec![
+                            #((
+                                #names,
+                                #krate::OutputBuilder::<#types>::finish(self.fields.#positions)?,
+                            ),)*
+                        ],
+                        self.validity,
+                    )
+                }
+            }
+        };
+    })
+}
@@ -0,0 +1,91 @@
+//! Compares writing UDF results through a materialized `Vec<Option<T>>` with
+//! appending them to a [`ToArrow::builder`] sized for the batch.
+//!
+//! Run with `cargo bench -p athena-udf --bench output`; the allocation counts are
+//! reported next to the timings.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BinaryArray, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use athena_udf::{OutputBuilder, ToArrow, UDFProcessor};
+use divan::{AllocProfiler, Bencher};
+
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
+
+fn main() {
+    divan::main();
+}
+
+const ROWS: &[usize] = &[1_024, 8_192];
+
+fn label(row: usize) -> String {
+    format!("customer-{}", row)
+}
+
+/// The path results took before the builders: a `Vec` of the whole batch that
+/// the array is then collected from.
+fn materialized<A, T>(rows: usize, value: impl Fn(usize) -> T) -> ArrayRef
+where
+    A: FromIterator<Option<T>> + Array + 'static,
+{
+    let values = (0..rows)
+        .map(|row| (row % 10 != 0).then(|| value(row)))
+        .collect::<Vec<_>>();
+    Arc::new(A::from_iter(values))
+}
+
+fn appended<T: ToArrow>(rows: usize, value: impl Fn(usize) -> T) -> ArrayRef {
+    let mut builder = T::builder(rows, None).unwrap();
+    for row in 0..rows {
+        builder
+            .append_option((row % 10 != 0).then(|| value(row)))
+            .unwrap();
+    }
+    builder.finish().unwrap()
+}
+
+mod string {
+    use super::*;
+
+    #[divan::bench(args = ROWS)]
+    fn materialized(bencher: Bencher, rows: usize) {
+        bencher.bench(|| super::materialized::<StringArray, _>(rows, label));
+    }
+
+    #[divan::bench(args = ROWS)]
+    fn builder(bencher: Bencher, rows: usize) {
+        bencher.bench(|| appended(rows, label));
+    }
+}
+
+mod binary {
+    use super::*;
+
+    #[divan::bench(args = ROWS)]
+    fn materialized(bencher: Bencher, rows: usize) {
+        bencher
+            .bench(|| super::materialized::<BinaryArray, _>(rows, |row| label(row).into_bytes()));
+    }
+
+    #[divan::bench(args = ROWS)]
+    fn builder(bencher: Bencher, rows: usize) {
+        bencher.bench(|| appended(rows, |row| label(row).into_bytes()));
+    }
+}
+
+/// The whole row loop of a string UDF, which now appends to the output builder.
+#[divan::bench(args = ROWS)]
+fn process_unary_string(bencher: Bencher, rows: usize) {
+    let schema = Arc::new(Schema::new(vec![Field::new("name", DataType::Utf8, true)]));
+    let names = StringArray::from_iter((0..rows).map(|row| Some(label(row))));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(names)]).unwrap();
+
+    bencher.bench(|| {
+        UDFProcessor::new(&batch)
+            .process_unary::<&str, String, _>("upper", str::to_uppercase)
+            .unwrap()
+    });
+}
@@ -1,8 +1,10 @@
 use arrow::array::*;
-use arrow::array::{
-    Array, ArrayRef, BinaryArray, BooleanArray, Float64Array, Int64Array, StringArray,
+use arrow::array::{Array, ArrayRef, BinaryArray, BooleanArray, StringArray};
+use arrow::datatypes::{
+    ArrowNativeType, ArrowPrimitiveType, DataType, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type,
 };
-use arrow::datatypes::DataType;
+use arrow::error::ArrowError;
 use std::sync::Arc;
 
 /// Trait for converting from Arrow arrays to Rust types.
@@ -23,10 +25,72 @@ use std::sync::Arc;
 /// assert_eq!(String::from_array(&array, 2), Some("world".to_string()));
 /// ```
 pub trait FromArrow: Sized {
-    type ArrayType: Array + 'static;
+    /// A typed view over an input column, borrowed from the Arrow array.
+    type Column<'a>;
+
+    /// Whether null values are passed to the UDF, i.e. `from_column` returns
+    /// `Some` for null values. True for `Option<T>`.
+    const NULLABLE: bool = false;
+
+    /// Creates a column view from an input array.
+    ///
+    /// Implementations dispatch on the runtime `DataType` of `array`, so one Rust
+    /// type can be read from several Arrow layouts (e.g. timestamps in any unit).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array's data type cannot be read as `Self`.
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError>;
+
+    /// Extracts the value at `index` from a column view.
+    ///
+    /// Returns `None` if the value is null and `Self` cannot represent it, which
+    /// makes the result of the row NULL. [`NULLABLE`](Self::NULLABLE) types like
+    /// `Option<T>` return `Some(None)` instead, so the UDF is called with `None`.
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self>;
 
-    fn from_array(array: &Self::ArrayType, index: usize) -> Option<Self>;
     fn array_type() -> DataType;
+
+    /// Extracts a single value from an array.
+    ///
+    /// Returns `None` for null values and for arrays that cannot be read as `Self`.
+    fn from_array(array: &dyn Array, index: usize) -> Option<Self> {
+        let column = Self::column(array).ok()?;
+        Self::from_column(&column, index)
+    }
+}
+
+/// Downcasts an input array to a concrete Arrow array type.
+///
+/// This is the building block for [`FromArrow::column`] implementations that read
+/// a single Arrow layout.
+///
+/// # Errors
+///
+/// Returns an error naming the expected and actual data types if the downcast fails.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{Array, Int64Array, StringArray};
+/// # use arrow::datatypes::DataType;
+/// # use athena_udf::arrow_conversions::downcast_array;
+/// let array = StringArray::from(vec!["a"]);
+///
+/// assert!(downcast_array::<StringArray>(&array, &DataType::Utf8).is_ok());
+/// assert!(downcast_array::<Int64Array>(&array, &DataType::Int64).is_err());
+/// ```
+pub fn downcast_array<'a, A: Array + 'static>(
+    array: &'a dyn Array,
+    expected: &DataType,
+) -> Result<&'a A, ArrowError> {
+    array.as_any().downcast_ref::<A>().ok_or_else(|| {
+        ArrowError::CastError(format!(
+            "expected {} but got {}",
+            expected,
+            array.data_type()
+        ))
+    })
 }
 
 /// Trait for converting from Rust types to Arrow arrays.
@@ -48,88 +112,352 @@ pub trait FromArrow: Sized {
 /// assert!(string_array.is_null(1));
 /// assert_eq!(string_array.value(2), "world");
 /// ```
-pub trait ToArrow {
+pub trait ToArrow: Sized {
     type ArrayType: Array + 'static;
 
-    fn to_array(values: Vec<Option<Self>>) -> ArrayRef
-    where
-        Self: Sized;
+    /// Accumulates values of this type into an output array.
+    type Builder: OutputBuilder<Self>;
+
     fn data_type() -> DataType;
+
+    /// Creates a builder with room for `capacity` values.
+    ///
+    /// The UDF processor calls this with the output field type from Athena's
+    /// `outputSchema`, so types whose physical layout depends on it (such as the
+    /// precision and scale of a decimal) can honor the request. Without a
+    /// requested type, values are written as [`ToArrow::data_type`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if values of this type cannot be written as `data_type`.
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError>;
+
+    /// Converts materialized values into an Arrow array.
+    fn to_array(values: Vec<Option<Self>>) -> ArrayRef {
+        collect_array::<Self>(values, None).expect("values match their own data type")
+    }
+
+    /// Converts materialized values into an Arrow array of the requested output type.
+    fn to_array_as(
+        values: Vec<Option<Self>>,
+        data_type: &DataType,
+    ) -> Result<ArrayRef, ArrowError> {
+        collect_array::<Self>(values, Some(data_type))
+    }
 }
 
-/// Converts `String` values from Arrow UTF-8 arrays.
+/// Appends UDF results of type `T` to an Arrow array under construction.
 ///
-/// Returns `None` for null values in the array.
-impl FromArrow for String {
-    type ArrayType = StringArray;
+/// Builders are created with [`ToArrow::builder`], sized for the rows of a
+/// batch, so the processor writes every result as soon as it is computed
+/// instead of collecting them first.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{Array, AsArray};
+/// # use athena_udf::arrow_conversions::{OutputBuilder, ToArrow};
+/// let mut builder = String::builder(2, None).unwrap();
+/// builder.append_value("hello".to_string()).unwrap();
+/// builder.append_null();
+/// let array = builder.finish().unwrap();
+///
+/// assert_eq!(array.as_string::<i32>().value(0), "hello");
+/// assert!(array.is_null(1));
+/// ```
+pub trait OutputBuilder<T> {
+    /// Appends a value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be represented in the output type,
+    /// e.g. a decimal that exceeds the requested precision. A nested value may
+    /// have been written in part by then, so the builder must not be used
+    /// after an error.
+    fn append_value(&mut self, value: T) -> Result<(), ArrowError>;
+
+    /// Appends a null.
+    fn append_null(&mut self);
+
+    /// Appends a value, or a null for `None`.
+    fn append_option(&mut self, value: Option<T>) -> Result<(), ArrowError> {
+        match value {
+            Some(value) => self.append_value(value),
+            None => {
+                self.append_null();
+                Ok(())
+            }
+        }
+    }
 
-    fn from_array(array: &Self::ArrayType, index: usize) -> Option<Self> {
-        if array.is_null(index) {
-            None
-        } else {
-            Some(array.value(index).to_string())
+    /// Builds the output array from the appended values.
+    fn finish(self) -> Result<ArrayRef, ArrowError>;
+}
+
+/// Bytes reserved per row for string and binary outputs.
+const STRING_CAPACITY: usize = 16;
+
+fn collect_array<T: ToArrow>(
+    values: Vec<Option<T>>,
+    data_type: Option<&DataType>,
+) -> Result<ArrayRef, ArrowError> {
+    let mut builder = T::builder(values.len(), data_type)?;
+    for value in values {
+        builder.append_option(value)?;
+    }
+    builder.finish()
+}
+
+/// Implements [`OutputBuilder`] for an Arrow builder that writes one layout
+/// chosen from several, e.g. `Utf8`, `LargeUtf8` or `Utf8View` for strings.
+macro_rules! layout_builder {
+    ($name:ident<$value:ty>, $($variant:ident($builder:ty)),+) => {
+        impl OutputBuilder<$value> for $name {
+            fn append_value(&mut self, value: $value) -> Result<(), ArrowError> {
+                match self {
+                    $(Self::$variant(builder) => builder.append_value(value),)+
+                }
+                Ok(())
+            }
+
+            fn append_null(&mut self) {
+                match self {
+                    $(Self::$variant(builder) => builder.append_null(),)+
+                }
+            }
+
+            fn finish(self) -> Result<ArrayRef, ArrowError> {
+                Ok(match self {
+                    $(Self::$variant(mut builder) => Arc::new(builder.finish()),)+
+                })
+            }
         }
+    };
+}
+
+/// Output builder for `String` values in any Arrow string layout.
+pub enum StringOutputBuilder {
+    Utf8(StringBuilder),
+    LargeUtf8(LargeStringBuilder),
+    Utf8View(StringViewBuilder),
+}
+
+layout_builder!(
+    StringOutputBuilder<String>,
+    Utf8(StringBuilder),
+    LargeUtf8(LargeStringBuilder),
+    Utf8View(StringViewBuilder)
+);
+
+/// Output builder for `Vec<u8>` values in any Arrow binary layout.
+pub enum BinaryOutputBuilder {
+    Binary(BinaryBuilder),
+    LargeBinary(LargeBinaryBuilder),
+    BinaryView(BinaryViewBuilder),
+}
+
+layout_builder!(
+    BinaryOutputBuilder<Vec<u8>>,
+    Binary(BinaryBuilder),
+    LargeBinary(LargeBinaryBuilder),
+    BinaryView(BinaryViewBuilder)
+);
+
+/// Output builder for values written with a single Arrow builder.
+pub struct ArrowOutputBuilder<B>(pub B);
+
+impl<T: ArrowPrimitive> OutputBuilder<T> for ArrowOutputBuilder<PrimitiveBuilder<T::ArrowType>> {
+    fn append_value(&mut self, value: T) -> Result<(), ArrowError> {
+        self.0.append_value(value);
+        Ok(())
     }
 
-    fn array_type() -> DataType {
-        DataType::Utf8
+    fn append_null(&mut self) {
+        self.0.append_null();
+    }
+
+    fn finish(mut self) -> Result<ArrayRef, ArrowError> {
+        Ok(Arc::new(self.0.finish()))
     }
 }
 
-/// Converts 64-bit signed integers from Arrow Int64 arrays.
-///
-/// Returns `None` for null values in the array.
-impl FromArrow for i64 {
-    type ArrayType = Int64Array;
+impl OutputBuilder<bool> for ArrowOutputBuilder<BooleanBuilder> {
+    fn append_value(&mut self, value: bool) -> Result<(), ArrowError> {
+        self.0.append_value(value);
+        Ok(())
+    }
 
-    fn from_array(array: &Self::ArrayType, index: usize) -> Option<Self> {
-        if array.is_null(index) {
-            None
-        } else {
-            Some(array.value(index))
+    fn append_null(&mut self) {
+        self.0.append_null();
+    }
+
+    fn finish(mut self) -> Result<ArrayRef, ArrowError> {
+        Ok(Arc::new(self.0.finish()))
+    }
+}
+
+/// Output builder for `Option<T>`, writing `None` values as nulls.
+pub struct OptionOutputBuilder<B>(B);
+
+impl<T, B: OutputBuilder<T>> OutputBuilder<Option<T>> for OptionOutputBuilder<B> {
+    fn append_value(&mut self, value: Option<T>) -> Result<(), ArrowError> {
+        self.0.append_option(value)
+    }
+
+    fn append_null(&mut self) {
+        self.0.append_null();
+    }
+
+    fn finish(self) -> Result<ArrayRef, ArrowError> {
+        self.0.finish()
+    }
+}
+
+/// Column view over any Arrow string layout: `Utf8`, `LargeUtf8` or `Utf8View`.
+pub enum StringColumn<'a> {
+    Utf8(&'a StringArray),
+    LargeUtf8(&'a LargeStringArray),
+    Utf8View(&'a StringViewArray),
+}
+
+impl<'a> StringColumn<'a> {
+    /// Creates a column view, choosing the layout from the array's data type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array is not a string array.
+    pub fn try_new(array: &'a dyn Array) -> Result<Self, ArrowError> {
+        match array.data_type() {
+            DataType::Utf8 => Ok(Self::Utf8(array.as_string::<i32>())),
+            DataType::LargeUtf8 => Ok(Self::LargeUtf8(array.as_string::<i64>())),
+            DataType::Utf8View => Ok(Self::Utf8View(array.as_string_view())),
+            other => Err(ArrowError::CastError(format!(
+                "expected Utf8 but got {}",
+                other
+            ))),
         }
     }
 
-    fn array_type() -> DataType {
-        DataType::Int64
+    /// Returns the string at `index`, or `None` if it is null.
+    pub fn value(&self, index: usize) -> Option<&'a str> {
+        match self {
+            Self::Utf8(array) => array.is_valid(index).then(|| array.value(index)),
+            Self::LargeUtf8(array) => array.is_valid(index).then(|| array.value(index)),
+            Self::Utf8View(array) => array.is_valid(index).then(|| array.value(index)),
+        }
     }
 }
 
-/// Converts 32-bit signed integers from Arrow Int32 arrays.
+/// Column view over any Arrow binary layout: `Binary`, `LargeBinary` or `BinaryView`.
+pub enum BinaryColumn<'a> {
+    Binary(&'a BinaryArray),
+    LargeBinary(&'a LargeBinaryArray),
+    BinaryView(&'a BinaryViewArray),
+}
+
+impl<'a> BinaryColumn<'a> {
+    /// Creates a column view, choosing the layout from the array's data type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array is not a binary array.
+    pub fn try_new(array: &'a dyn Array) -> Result<Self, ArrowError> {
+        match array.data_type() {
+            DataType::Binary => Ok(Self::Binary(array.as_binary::<i32>())),
+            DataType::LargeBinary => Ok(Self::LargeBinary(array.as_binary::<i64>())),
+            DataType::BinaryView => Ok(Self::BinaryView(array.as_binary_view())),
+            other => Err(ArrowError::CastError(format!(
+                "expected Binary but got {}",
+                other
+            ))),
+        }
+    }
+
+    /// Returns the bytes at `index`, or `None` if they are null.
+    pub fn value(&self, index: usize) -> Option<&'a [u8]> {
+        match self {
+            Self::Binary(array) => array.is_valid(index).then(|| array.value(index)),
+            Self::LargeBinary(array) => array.is_valid(index).then(|| array.value(index)),
+            Self::BinaryView(array) => array.is_valid(index).then(|| array.value(index)),
+        }
+    }
+}
+
+/// Converts `String` values from Arrow string arrays.
 ///
+/// Accepts `Utf8`, `LargeUtf8` and `Utf8View` columns.
 /// Returns `None` for null values in the array.
-impl FromArrow for i32 {
-    type ArrayType = Int32Array;
+impl FromArrow for String {
+    type Column<'a> = StringColumn<'a>;
 
-    fn from_array(array: &Self::ArrayType, index: usize) -> Option<Self> {
-        if array.is_null(index) {
-            None
-        } else {
-            Some(array.value(index))
-        }
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        StringColumn::try_new(array)
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        column.value(index).map(str::to_string)
     }
 
     fn array_type() -> DataType {
-        DataType::Int32
+        DataType::Utf8
     }
 }
 
-/// Converts 64-bit floating point numbers from Arrow Float64 arrays.
+/// Rust primitive types that map directly to an Arrow primitive array.
+///
+/// Implemented for `i8`, `i16`, `i32`, `i64`, `f32` and `f64`, which correspond to
+/// Athena `TINYINT`, `SMALLINT`, `INTEGER`, `BIGINT`, `REAL` and `DOUBLE`. Their
+/// [`FromArrow`] and [`ToArrow`] implementations are shared through this mapping.
+pub trait ArrowPrimitive: ArrowNativeType {
+    /// The Arrow primitive type whose native representation is `Self`.
+    type ArrowType: ArrowPrimitiveType<Native = Self>;
+}
+
+impl ArrowPrimitive for i8 {
+    type ArrowType = Int8Type;
+}
+
+impl ArrowPrimitive for i16 {
+    type ArrowType = Int16Type;
+}
+
+impl ArrowPrimitive for i32 {
+    type ArrowType = Int32Type;
+}
+
+impl ArrowPrimitive for i64 {
+    type ArrowType = Int64Type;
+}
+
+impl ArrowPrimitive for f32 {
+    type ArrowType = Float32Type;
+}
+
+impl ArrowPrimitive for f64 {
+    type ArrowType = Float64Type;
+}
+
+/// Converts primitive numbers from the matching Arrow primitive arrays,
+/// e.g. `i16` from `Int16Array`.
 ///
 /// Returns `None` for null values in the array.
-impl FromArrow for f64 {
-    type ArrayType = Float64Array;
+impl<T: ArrowPrimitive> FromArrow for T {
+    type Column<'a> = &'a PrimitiveArray<T::ArrowType>;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        downcast_array(array, &Self::array_type())
+    }
 
-    fn from_array(array: &Self::ArrayType, index: usize) -> Option<Self> {
-        if array.is_null(index) {
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        if column.is_null(index) {
             None
         } else {
-            Some(array.value(index))
+            Some(column.value(index))
         }
     }
 
     fn array_type() -> DataType {
-        DataType::Float64
+        T::ArrowType::DATA_TYPE
     }
 }
 
@@ -137,13 +465,17 @@ impl FromArrow for f64 {
 ///
 /// Returns `None` for null values in the array.
 impl FromArrow for bool {
-    type ArrayType = BooleanArray;
+    type Column<'a> = &'a BooleanArray;
 
-    fn from_array(array: &Self::ArrayType, index: usize) -> Option<Self> {
-        if array.is_null(index) {
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        downcast_array(array, &Self::array_type())
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        if column.is_null(index) {
             None
         } else {
-            Some(array.value(index))
+            Some(column.value(index))
         }
     }
 
@@ -152,18 +484,19 @@ impl FromArrow for bool {
     }
 }
 
-/// Converts binary data from Arrow Binary arrays.
+/// Converts binary data from Arrow binary arrays.
 ///
+/// Accepts `Binary`, `LargeBinary` and `BinaryView` columns.
 /// Returns `None` for null values in the array.
 impl FromArrow for Vec<u8> {
-    type ArrayType = BinaryArray;
+    type Column<'a> = BinaryColumn<'a>;
 
-    fn from_array(array: &Self::ArrayType, index: usize) -> Option<Self> {
-        if array.is_null(index) {
-            None
-        } else {
-            Some(array.value(index).to_vec())
-        }
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        BinaryColumn::try_new(array)
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        column.value(index).map(<[u8]>::to_vec)
     }
 
     fn array_type() -> DataType {
@@ -173,61 +506,52 @@ impl FromArrow for Vec<u8> {
 
 /// Converts `String` values to Arrow UTF-8 arrays.
 ///
-/// Preserves `None` values as nulls in the resulting array.
+/// Preserves `None` values as nulls in the resulting array. When the requested
+/// output type is `LargeUtf8` or `Utf8View`, that layout is written instead.
 impl ToArrow for String {
     type ArrayType = StringArray;
-
-    fn to_array(values: Vec<Option<Self>>) -> ArrayRef {
-        Arc::new(StringArray::from(values))
-    }
+    type Builder = StringOutputBuilder;
 
     fn data_type() -> DataType {
         DataType::Utf8
     }
-}
-
-/// Converts 64-bit signed integers to Arrow Int64 arrays.
-///
-/// Preserves `None` values as nulls in the resulting array.
-impl ToArrow for i64 {
-    type ArrayType = Int64Array;
-
-    fn to_array(values: Vec<Option<Self>>) -> ArrayRef {
-        Arc::new(Int64Array::from(values))
-    }
 
-    fn data_type() -> DataType {
-        DataType::Int64
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        match data_type.unwrap_or(&DataType::Utf8) {
+            DataType::Utf8 => Ok(StringOutputBuilder::Utf8(StringBuilder::with_capacity(
+                capacity,
+                capacity * STRING_CAPACITY,
+            ))),
+            DataType::LargeUtf8 => Ok(StringOutputBuilder::LargeUtf8(
+                LargeStringBuilder::with_capacity(capacity, capacity * STRING_CAPACITY),
+            )),
+            DataType::Utf8View => Ok(StringOutputBuilder::Utf8View(
+                StringViewBuilder::with_capacity(capacity),
+            )),
+            other => Err(ArrowError::CastError(format!(
+                "Cannot write string values to a {} column",
+                other
+            ))),
+        }
     }
 }
 
-/// Converts 32-bit signed integers to Arrow Int32 arrays.
+/// Converts primitive numbers to the matching Arrow primitive arrays,
+/// e.g. `f32` to `Float32Array`.
 ///
 /// Preserves `None` values as nulls in the resulting array.
-impl ToArrow for i32 {
-    type ArrayType = Int32Array;
-
-    fn to_array(values: Vec<Option<Self>>) -> ArrayRef {
-        Arc::new(Int32Array::from(values))
-    }
+impl<T: ArrowPrimitive> ToArrow for T {
+    type ArrayType = PrimitiveArray<T::ArrowType>;
+    type Builder = ArrowOutputBuilder<PrimitiveBuilder<T::ArrowType>>;
 
     fn data_type() -> DataType {
-        DataType::Int32
+        T::ArrowType::DATA_TYPE
     }
-}
 
-/// Converts 64-bit floating point numbers to Arrow Float64 arrays.
-///
-/// Preserves `None` values as nulls in the resulting array.
-impl ToArrow for f64 {
-    type ArrayType = Float64Array;
-
-    fn to_array(values: Vec<Option<Self>>) -> ArrayRef {
-        Arc::new(Float64Array::from(values))
-    }
-
-    fn data_type() -> DataType {
-        DataType::Float64
+    fn builder(capacity: usize, _: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        Ok(ArrowOutputBuilder(PrimitiveBuilder::with_capacity(
+            capacity,
+        )))
     }
 }
 
@@ -236,29 +560,47 @@ impl ToArrow for f64 {
 /// Preserves `None` values as nulls in the resulting array.
 impl ToArrow for bool {
     type ArrayType = BooleanArray;
-
-    fn to_array(values: Vec<Option<Self>>) -> ArrayRef {
-        Arc::new(BooleanArray::from(values))
-    }
+    type Builder = ArrowOutputBuilder<BooleanBuilder>;
 
     fn data_type() -> DataType {
         DataType::Boolean
     }
+
+    fn builder(capacity: usize, _: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        Ok(ArrowOutputBuilder(BooleanBuilder::with_capacity(capacity)))
+    }
 }
 
 /// Converts binary data to Arrow Binary arrays.
 ///
-/// Preserves `None` values as nulls in the resulting array.
+/// Preserves `None` values as nulls in the resulting array. When the requested
+/// output type is `LargeBinary` or `BinaryView`, that layout is written instead.
 impl ToArrow for Vec<u8> {
     type ArrayType = BinaryArray;
-
-    fn to_array(values: Vec<Option<Self>>) -> ArrayRef {
-        Arc::new(BinaryArray::from_iter(values))
-    }
+    type Builder = BinaryOutputBuilder;
 
     fn data_type() -> DataType {
         DataType::Binary
     }
+
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        match data_type.unwrap_or(&DataType::Binary) {
+            DataType::Binary => Ok(BinaryOutputBuilder::Binary(BinaryBuilder::with_capacity(
+                capacity,
+                capacity * STRING_CAPACITY,
+            ))),
+            DataType::LargeBinary => Ok(BinaryOutputBuilder::LargeBinary(
+                LargeBinaryBuilder::with_capacity(capacity, capacity * STRING_CAPACITY),
+            )),
+            DataType::BinaryView => Ok(BinaryOutputBuilder::BinaryView(
+                BinaryViewBuilder::with_capacity(capacity),
+            )),
+            other => Err(ArrowError::CastError(format!(
+                "Cannot write binary values to a {} column",
+                other
+            ))),
+        }
+    }
 }
 
 /// Implements `FromArrow` for `Option<T>` where `T: FromArrow`.
@@ -285,10 +627,16 @@ impl ToArrow for Vec<u8> {
 /// assert_eq!(Option::<String>::from_array(&array, 1), Some(None));
 /// ```
 impl<T: FromArrow> FromArrow for Option<T> {
-    type ArrayType = T::ArrayType;
+    type Column<'a> = T::Column<'a>;
 
-    fn from_array(array: &Self::ArrayType, index: usize) -> Option<Self> {
-        Some(T::from_array(array, index))
+    const NULLABLE: bool = true;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        T::column(array)
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        Some(T::from_column(column, index))
     }
 
     fn array_type() -> DataType {
@@ -321,18 +669,161 @@ impl<T: FromArrow> FromArrow for Option<T> {
 /// ```
 impl<T: ToArrow> ToArrow for Option<T> {
     type ArrayType = T::ArrayType;
-
-    fn to_array(values: Vec<Option<Self>>) -> ArrayRef {
-        let flattened: Vec<Option<T>> = values.into_iter().map(|opt| opt.flatten()).collect();
-        T::to_array(flattened)
-    }
+    type Builder = OptionOutputBuilder<T::Builder>;
 
     fn data_type() -> DataType {
         T::data_type()
     }
+
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        T::builder(capacity, data_type).map(OptionOutputBuilder)
+    }
+}
+
+/// Trait for reading UDF arguments that may borrow from the input batch.
+///
+/// This is the lifetime-parameterized counterpart of [`FromArrow`] used by the UDF
+/// processor. Every `FromArrow` type implements it and yields owned values, while
+/// `&str` and `&[u8]` (and their `Option` forms) yield slices borrowed from the
+/// Arrow buffers, so read-only string functions do not allocate per row.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::StringArray;
+/// # use athena_udf::arrow_conversions::FromArrowRef;
+/// let array = StringArray::from(vec![Some("hello"), None]);
+/// let column = <&str>::ref_column(&array).unwrap();
+///
+/// assert_eq!(<&str>::ref_value(&column, 0), Some("hello"));
+/// assert_eq!(<&str>::ref_value(&column, 1), None);
+/// assert_eq!(<Option<&str>>::ref_value(&column, 1), Some(None));
+/// ```
+pub trait FromArrowRef {
+    /// A typed view over an input column, borrowed from the Arrow array.
+    type Column<'a>;
+
+    /// The argument type passed to the UDF, which may borrow from the column.
+    type Value<'a>;
+
+    /// See [`FromArrow::NULLABLE`].
+    const NULLABLE: bool = false;
+
+    /// Creates a column view from an input array.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array's data type cannot be read as `Self`.
+    fn ref_column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError>;
+
+    /// Extracts the value at `index` from a column view, or `None` if it is null.
+    fn ref_value<'a>(column: &Self::Column<'a>, index: usize) -> Option<Self::Value<'a>>;
+
+    /// The Arrow type input columns are coerced to when they cannot be read directly.
+    fn ref_array_type() -> DataType;
+}
+
+impl<T: FromArrow> FromArrowRef for T {
+    type Column<'a> = T::Column<'a>;
+    type Value<'a> = T;
+
+    const NULLABLE: bool = T::NULLABLE;
+
+    fn ref_column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        T::column(array)
+    }
+
+    fn ref_value<'a>(column: &Self::Column<'a>, index: usize) -> Option<Self::Value<'a>> {
+        T::from_column(column, index)
+    }
+
+    fn ref_array_type() -> DataType {
+        T::array_type()
+    }
+}
+
+/// Borrows string slices from Arrow string arrays without copying.
+///
+/// Accepts the same layouts as `String`. Returns `None` for null values in the array.
+impl FromArrowRef for &str {
+    type Column<'a> = StringColumn<'a>;
+    type Value<'a> = &'a str;
+
+    fn ref_column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        StringColumn::try_new(array)
+    }
+
+    fn ref_value<'a>(column: &Self::Column<'a>, index: usize) -> Option<Self::Value<'a>> {
+        column.value(index)
+    }
+
+    fn ref_array_type() -> DataType {
+        DataType::Utf8
+    }
+}
+
+/// Borrows string slices from Arrow string arrays, passing nulls as `None`.
+impl FromArrowRef for Option<&str> {
+    type Column<'a> = StringColumn<'a>;
+    type Value<'a> = Option<&'a str>;
+
+    const NULLABLE: bool = true;
+
+    fn ref_column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        StringColumn::try_new(array)
+    }
+
+    fn ref_value<'a>(column: &Self::Column<'a>, index: usize) -> Option<Self::Value<'a>> {
+        Some(column.value(index))
+    }
+
+    fn ref_array_type() -> DataType {
+        DataType::Utf8
+    }
+}
+
+/// Borrows byte slices from Arrow binary arrays without copying.
+///
+/// Accepts the same layouts as `Vec<u8>`. Returns `None` for null values in the array.
+impl FromArrowRef for &[u8] {
+    type Column<'a> = BinaryColumn<'a>;
+    type Value<'a> = &'a [u8];
+
+    fn ref_column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        BinaryColumn::try_new(array)
+    }
+
+    fn ref_value<'a>(column: &Self::Column<'a>, index: usize) -> Option<Self::Value<'a>> {
+        column.value(index)
+    }
+
+    fn ref_array_type() -> DataType {
+        DataType::Binary
+    }
+}
+
+/// Borrows byte slices from Arrow binary arrays, passing nulls as `None`.
+impl FromArrowRef for Option<&[u8]> {
+    type Column<'a> = BinaryColumn<'a>;
+    type Value<'a> = Option<&'a [u8]>;
+
+    const NULLABLE: bool = true;
+
+    fn ref_column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        BinaryColumn::try_new(array)
+    }
+
+    fn ref_value<'a>(column: &Self::Column<'a>, index: usize) -> Option<Self::Value<'a>> {
+        Some(column.value(index))
+    }
+
+    fn ref_array_type() -> DataType {
+        DataType::Binary
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant, clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -405,6 +896,31 @@ mod tests {
         assert_eq!(float_array.value(2), -2.71);
     }
 
+    #[test]
+    fn test_small_primitives_from_arrow() {
+        let tiny = Int8Array::from(vec![Some(-8), None]);
+        let small = Int16Array::from(vec![Some(1600)]);
+        let real = Float32Array::from(vec![Some(1.5)]);
+
+        assert_eq!(i8::from_array(&tiny, 0), Some(-8));
+        assert_eq!(i8::from_array(&tiny, 1), None);
+        assert_eq!(i16::from_array(&small, 0), Some(1600));
+        assert_eq!(f32::from_array(&real, 0), Some(1.5));
+        assert_eq!(i16::from_array(&tiny, 0), None);
+    }
+
+    #[test]
+    fn test_small_primitives_to_arrow() {
+        let tiny = i8::to_array(vec![Some(1), None]);
+        let small = i16::to_array(vec![Some(2)]);
+        let real = f32::to_array(vec![Some(2.5)]);
+
+        assert_eq!(tiny.as_primitive::<Int8Type>().value(0), 1);
+        assert!(tiny.is_null(1));
+        assert_eq!(small.as_primitive::<Int16Type>().value(0), 2);
+        assert_eq!(real.as_primitive::<Float32Type>().value(0), 2.5);
+    }
+
     #[test]
     fn test_bool_from_arrow() {
         let array = BooleanArray::from(vec![Some(true), None, Some(false)]);
@@ -425,15 +941,115 @@ mod tests {
         assert_eq!(bool_array.value(2), false);
     }
 
+    #[test]
+    fn test_string_from_large_and_view_arrays() {
+        let large = LargeStringArray::from(vec![Some("large"), None]);
+        let view = StringViewArray::from(vec![Some("a string longer than twelve bytes"), None]);
+
+        assert_eq!(String::from_array(&large, 0), Some("large".to_string()));
+        assert_eq!(String::from_array(&large, 1), None);
+        assert_eq!(
+            String::from_array(&view, 0),
+            Some("a string longer than twelve bytes".to_string())
+        );
+        assert_eq!(Option::<String>::from_array(&view, 1), Some(None));
+        assert!(String::column(&Int64Array::from(vec![1])).is_err());
+    }
+
+    #[test]
+    fn test_binary_from_large_and_view_arrays() {
+        let large = LargeBinaryArray::from(vec![Some(b"ab".as_ref()), None]);
+        let view = BinaryViewArray::from(vec![Some(b"cd".as_ref())]);
+
+        assert_eq!(Vec::<u8>::from_array(&large, 0), Some(b"ab".to_vec()));
+        assert_eq!(Vec::<u8>::from_array(&large, 1), None);
+        assert_eq!(Vec::<u8>::from_array(&view, 0), Some(b"cd".to_vec()));
+    }
+
+    #[test]
+    fn test_string_to_arrow_uses_requested_layout() {
+        let values = vec![Some("x".to_string()), None];
+
+        let large = String::to_array_as(values.clone(), &DataType::LargeUtf8).unwrap();
+        assert_eq!(large.data_type(), &DataType::LargeUtf8);
+        assert_eq!(large.as_string::<i64>().value(0), "x");
+
+        let view = String::to_array_as(values.clone(), &DataType::Utf8View).unwrap();
+        assert_eq!(view.as_string_view().value(0), "x");
+        assert!(view.is_null(1));
+
+        assert!(String::to_array_as(values, &DataType::Int64).is_err());
+    }
+
+    #[test]
+    fn test_binary_to_arrow_uses_requested_layout() {
+        let values = vec![Some(b"x".to_vec()), None];
+
+        let large = Vec::<u8>::to_array_as(values.clone(), &DataType::LargeBinary).unwrap();
+        assert_eq!(large.as_binary::<i64>().value(0), b"x");
+
+        let view = Vec::<u8>::to_array_as(values, &DataType::BinaryView).unwrap();
+        assert_eq!(view.as_binary_view().value(0), b"x");
+        assert!(view.is_null(1));
+    }
+
+    #[test]
+    fn test_output_builder_appends_values_and_nulls() {
+        let mut builder = Option::<String>::builder(3, Some(&DataType::LargeUtf8)).unwrap();
+        builder.append_value(Some("hello".to_string())).unwrap();
+        builder.append_value(None).unwrap();
+        builder.append_null();
+        let array = builder.finish().unwrap();
+
+        assert_eq!(array.data_type(), &DataType::LargeUtf8);
+        assert_eq!(array.as_string::<i64>().value(0), "hello");
+        assert_eq!(array.null_count(), 2);
+        assert!(String::builder(1, Some(&DataType::Int64)).is_err());
+    }
+
+    #[test]
+    fn test_borrowed_str_from_arrow() {
+        let array = StringArray::from(vec![Some("hello"), None]);
+        let column = <&str>::ref_column(&array).unwrap();
+
+        assert_eq!(<&str>::ref_value(&column, 0), Some("hello"));
+        assert_eq!(<&str>::ref_value(&column, 1), None);
+        assert_eq!(<Option<&str>>::ref_value(&column, 1), Some(None));
+
+        let view = StringViewArray::from(vec!["view"]);
+        let column = <&str>::ref_column(&view).unwrap();
+        assert_eq!(<&str>::ref_value(&column, 0), Some("view"));
+    }
+
+    #[test]
+    fn test_borrowed_bytes_from_arrow() {
+        let array = BinaryArray::from(vec![Some(b"abc".as_ref()), None]);
+        let column = <&[u8]>::ref_column(&array).unwrap();
+
+        assert_eq!(<&[u8]>::ref_value(&column, 0), Some(b"abc".as_ref()));
+        assert_eq!(<Option<&[u8]>>::ref_value(&column, 1), Some(None));
+        assert!(<&[u8]>::ref_column(&StringArray::from(vec!["abc"])).is_err());
+
+        let large = LargeBinaryArray::from(vec![b"large".as_ref()]);
+        let column = <&[u8]>::ref_column(&large).unwrap();
+        assert_eq!(<&[u8]>::ref_value(&column, 0), Some(b"large".as_ref()));
+    }
+
     #[test]
     fn test_data_types() {
         assert_eq!(String::array_type(), DataType::Utf8);
+        assert_eq!(i8::array_type(), DataType::Int8);
+        assert_eq!(i16::array_type(), DataType::Int16);
+        assert_eq!(f32::array_type(), DataType::Float32);
         assert_eq!(i64::array_type(), DataType::Int64);
         assert_eq!(i32::array_type(), DataType::Int32);
         assert_eq!(f64::array_type(), DataType::Float64);
         assert_eq!(bool::array_type(), DataType::Boolean);
 
         assert_eq!(String::data_type(), DataType::Utf8);
+        assert_eq!(i8::data_type(), DataType::Int8);
+        assert_eq!(i16::data_type(), DataType::Int16);
+        assert_eq!(f32::data_type(), DataType::Float32);
         assert_eq!(i64::data_type(), DataType::Int64);
         assert_eq!(i32::data_type(), DataType::Int32);
         assert_eq!(f64::data_type(), DataType::Float64);
@@ -0,0 +1,196 @@
+use arrow::array::ArrayRef;
+use arrow::compute::{can_cast_types, cast_with_options, CastOptions};
+use arrow::datatypes::DataType;
+
+/// How input columns are converted when their Arrow type differs from the type
+/// a UDF parameter reads.
+///
+/// Athena does not always hand a UDF the exact type it was declared with, e.g. an
+/// `INTEGER` column passed to a `BIGINT` parameter. The processor then casts the
+/// column to the parameter type before reading it. The same rules apply to a
+/// result whose Arrow type differs from the requested output type.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{Array, Int32Array, Int64Array};
+/// # use arrow::datatypes::{DataType, Field, Schema};
+/// # use arrow::record_batch::RecordBatch;
+/// # use std::sync::Arc;
+/// # use athena_udf::{Coercion, UDFProcessor};
+/// let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, true)]));
+/// let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![41]))]).unwrap();
+///
+/// // INTEGER widens to i64 without any configuration
+/// let output_batch = UDFProcessor::new(&batch)
+///     .process_unary::<i64, i64, _>("next", |n| n + 1)
+///     .unwrap();
+/// let output_array = output_batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+/// assert_eq!(output_array.value(0), 42);
+///
+/// // Narrowing to i32 needs lenient coercion
+/// let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+/// let batch = RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(vec![41]))]).unwrap();
+/// assert!(UDFProcessor::new(&batch)
+///     .process_unary::<i32, i32, _>("next", |n| n + 1)
+///     .is_err());
+/// assert!(UDFProcessor::new(&batch)
+///     .with_coercion(Coercion::Lenient)
+///     .process_unary::<i32, i32, _>("next", |n| n + 1)
+///     .is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Coercion {
+    /// Only lossless widenings are applied: smaller integers to larger integers,
+    /// integers to floating point types that represent them exactly, `REAL` to
+    /// `DOUBLE`, and string or binary columns to their large layouts.
+    #[default]
+    Strict,
+    /// Any cast Arrow supports is applied. Values that do not survive the cast,
+    /// such as an out-of-range `BIGINT` read as `i32`, fail the batch instead of
+    /// becoming null.
+    Lenient,
+}
+
+impl Coercion {
+    /// Casts an input column to the type a UDF parameter reads.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the column and both types if the cast is not
+    /// allowed by this coercion mode or a value cannot be converted.
+    pub(crate) fn coerce(
+        self,
+        col_idx: usize,
+        array: &ArrayRef,
+        to: &DataType,
+    ) -> Result<ArrayRef, String> {
+        self.cast(array, to).map_err(|reason| {
+            format!(
+                "Column {} type mismatch: cannot coerce {} to {}: {}",
+                col_idx,
+                array.data_type(),
+                to,
+                reason
+            )
+        })
+    }
+
+    /// Whether this coercion mode casts `from` to `to`.
+    pub(crate) fn allows(self, from: &DataType, to: &DataType) -> bool {
+        match self {
+            Coercion::Strict => is_widening(from, to),
+            Coercion::Lenient => can_cast_types(from, to),
+        }
+    }
+
+    /// Casts an array to `to` if this coercion mode allows it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason if the cast is not allowed or a value cannot be
+    /// converted.
+    pub(crate) fn cast(self, array: &ArrayRef, to: &DataType) -> Result<ArrayRef, String> {
+        let from = array.data_type();
+        if !self.allows(from, to) {
+            let reason = match self {
+                Coercion::Strict if can_cast_types(from, to) => {
+                    "the conversion may lose data and requires lenient coercion"
+                }
+                _ => "no conversion exists",
+            };
+            return Err(reason.to_string());
+        }
+
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        cast_with_options(array, to, &options).map_err(|e| e.to_string())
+    }
+}
+
+/// Returns whether every value of `from` can be represented exactly in `to`.
+fn is_widening(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+
+    match (from, to) {
+        (Int8, Int16 | Int32 | Int64 | Float32 | Float64) => true,
+        (Int16, Int32 | Int64 | Float32 | Float64) => true,
+        (Int32, Int64 | Float64) => true,
+        (Float16, Float32 | Float64) => true,
+        (Float32, Float64) => true,
+        (Utf8, LargeUtf8) => true,
+        (Binary, LargeBinary) => true,
+        (List(from), List(to) | LargeList(to)) | (LargeList(from), LargeList(to)) => {
+            from.data_type() == to.data_type() || is_widening(from.data_type(), to.data_type())
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Float32Array, Int16Array, Int64Array, ListArray, StringArray};
+    use arrow::datatypes::{Field, Int32Type};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_strict_widens_integers_and_floats() {
+        let array: ArrayRef = Arc::new(Int16Array::from(vec![Some(7), None]));
+        let coerced = Coercion::Strict
+            .coerce(0, &array, &DataType::Int64)
+            .unwrap();
+
+        assert_eq!(coerced.data_type(), &DataType::Int64);
+        assert!(coerced.is_null(1));
+
+        let array: ArrayRef = Arc::new(Float32Array::from(vec![1.5]));
+        let coerced = Coercion::Strict
+            .coerce(0, &array, &DataType::Float64)
+            .unwrap();
+        assert_eq!(coerced.data_type(), &DataType::Float64);
+    }
+
+    #[test]
+    fn test_strict_widens_list_elements() {
+        let array: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2)]),
+        ]));
+        let to = DataType::List(Arc::new(Field::new_list_field(DataType::Int64, true)));
+
+        assert_eq!(
+            Coercion::Strict.coerce(0, &array, &to).unwrap().data_type(),
+            &to
+        );
+    }
+
+    #[test]
+    fn test_strict_rejects_narrowing() {
+        let array: ArrayRef = Arc::new(Int64Array::from(vec![1]));
+        let err = Coercion::Strict
+            .coerce(2, &array, &DataType::Int32)
+            .unwrap_err();
+
+        assert!(err.contains("Column 2"));
+        assert!(err.contains("Int64 to Int32"));
+        assert!(err.contains("lenient"));
+    }
+
+    #[test]
+    fn test_lenient_fails_on_lossy_values() {
+        let array: ArrayRef = Arc::new(Int64Array::from(vec![1, i64::MAX]));
+        let err = Coercion::Lenient
+            .coerce(0, &array, &DataType::Int32)
+            .unwrap_err();
+
+        assert!(err.contains("Int64 to Int32"));
+
+        let array: ArrayRef = Arc::new(StringArray::from(vec!["42"]));
+        let coerced = Coercion::Lenient
+            .coerce(0, &array, &DataType::Int64)
+            .unwrap();
+        assert_eq!(coerced.data_type(), &DataType::Int64);
+    }
+}
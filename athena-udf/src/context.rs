@@ -0,0 +1,54 @@
+use crate::request::{AthenaUDFRequest, Identity};
+use lambda_runtime::Context;
+
+/// Information about the Athena request a UDF is evaluated for.
+///
+/// The context is built once per request and can be passed to handler closures
+/// through [`handle_athena_request_with_context`](crate::handle_athena_request_with_context)
+/// and to UDFs whose first parameter is `&UdfContext`, e.g. for auditing or for
+/// behavior that depends on the calling principal.
+///
+/// # Examples
+///
+/// ```
+/// use athena_udf::UdfContext;
+///
+/// fn mask_email(context: &UdfContext, email: String) -> String {
+///     match context.identity.principal.as_deref() {
+///         Some("admin") => email,
+///         _ => "***".to_string(),
+///     }
+/// }
+///
+/// // Registered like any other UDF:
+/// //
+/// // athena_udf_handler! {
+/// //     "mask_email" => mask_email: (&UdfContext, String) -> String,
+/// // }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UdfContext {
+    /// The principal that issued the query.
+    pub identity: Identity,
+    /// The name of the UDF called by Athena.
+    pub method_name: String,
+    /// The function type sent by Athena, e.g. `SCALAR`.
+    pub function_type: String,
+    /// The id of the input records, which Athena expects back in the response.
+    pub a_id: String,
+    /// The Lambda invocation context, including the Lambda request id.
+    pub lambda: Context,
+}
+
+impl UdfContext {
+    /// Creates the context for a UDF request and its Lambda invocation.
+    pub fn from_request(request: &AthenaUDFRequest, lambda: Context) -> Self {
+        Self {
+            identity: request.identity.clone(),
+            method_name: request.method_name.clone(),
+            function_type: request.function_type.clone(),
+            a_id: request.input_records.a_id.clone(),
+            lambda,
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use crate::arrow_conversions::{downcast_array, FromArrow, OutputBuilder, ToArrow};
+use arrow::array::{Array, ArrayRef, Decimal128Array, Decimal128Builder};
+use arrow::datatypes::{DataType, DECIMAL128_MAX_PRECISION, DECIMAL_DEFAULT_SCALE};
+use arrow::error::ArrowError;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A fixed-point decimal number as used by Athena `DECIMAL(p,s)` columns.
+///
+/// The value is stored as an unscaled 128-bit integer together with its scale,
+/// so `Decimal::new(12345, 2)` represents `123.45`. This mirrors the layout of
+/// Arrow's `Decimal128Array` and never goes through floating point.
+///
+/// # Examples
+///
+/// ```
+/// # use athena_udf::Decimal;
+/// let price: Decimal = "19.99".parse().unwrap();
+/// assert_eq!(price.value(), 1999);
+/// assert_eq!(price.scale(), 2);
+///
+/// let rescaled = price.rescale(4).unwrap();
+/// assert_eq!(rescaled.to_string(), "19.9900");
+///
+/// // Dropping non-zero digits is an error, rounding has to be requested explicitly
+/// assert!(price.rescale(1).is_err());
+/// assert_eq!(price.round(1).unwrap().to_string(), "20.0");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    value: i128,
+    scale: i8,
+}
+
+impl Decimal {
+    /// Creates a decimal from its unscaled value and scale.
+    pub fn new(value: i128, scale: i8) -> Self {
+        Self { value, scale }
+    }
+
+    /// Returns the unscaled integer value.
+    pub fn value(&self) -> i128 {
+        self.value
+    }
+
+    /// Returns the number of digits after the decimal point.
+    pub fn scale(&self) -> i8 {
+        self.scale
+    }
+
+    /// Returns the number of significant digits of the unscaled value.
+    pub fn precision(&self) -> u8 {
+        self.value
+            .unsigned_abs()
+            .checked_ilog10()
+            .map_or(1, |log| log as u8 + 1)
+    }
+
+    /// Converts the decimal to another scale without losing information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value overflows 128 bits at the new scale, or if
+    /// reducing the scale would drop non-zero digits. Use [`Decimal::round`] when
+    /// rounding is acceptable.
+    pub fn rescale(&self, scale: i8) -> Result<Self, ArrowError> {
+        let diff = scale as i32 - self.scale as i32;
+        if diff >= 0 {
+            let value = pow10(diff as u32)
+                .and_then(|factor| self.value.checked_mul(factor))
+                .ok_or_else(|| {
+                    ArrowError::ArithmeticOverflow(format!(
+                        "Decimal {} overflows when rescaled to scale {}",
+                        self, scale
+                    ))
+                })?;
+            Ok(Self::new(value, scale))
+        } else {
+            let divisor = pow10(diff.unsigned_abs());
+            match divisor {
+                Some(divisor) if self.value % divisor == 0 => {
+                    Ok(Self::new(self.value / divisor, scale))
+                }
+                None if self.value == 0 => Ok(Self::new(0, scale)),
+                _ => Err(ArrowError::CastError(format!(
+                    "Decimal {} cannot be represented with scale {} without rounding",
+                    self, scale
+                ))),
+            }
+        }
+    }
+
+    /// Converts the decimal to another scale, rounding half away from zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value overflows 128 bits at the new scale.
+    pub fn round(&self, scale: i8) -> Result<Self, ArrowError> {
+        let diff = self.scale as i32 - scale as i32;
+        if diff <= 0 {
+            return self.rescale(scale);
+        }
+
+        let Some(divisor) = pow10(diff as u32) else {
+            return Ok(Self::new(0, scale));
+        };
+        let quotient = self.value / divisor;
+        let remainder = self.value % divisor;
+        let value = if remainder.unsigned_abs() * 2 >= divisor.unsigned_abs() {
+            quotient + self.value.signum()
+        } else {
+            quotient
+        };
+        Ok(Self::new(value, scale))
+    }
+}
+
+fn pow10(exponent: u32) -> Option<i128> {
+    10i128.checked_pow(exponent)
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.value < 0 { "-" } else { "" };
+        let digits = self.value.unsigned_abs().to_string();
+
+        if self.scale <= 0 {
+            let zeros = if self.value == 0 {
+                0
+            } else {
+                self.scale.unsigned_abs() as usize
+            };
+            return write!(f, "{}{}{}", sign, digits, "0".repeat(zeros));
+        }
+
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ArrowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ArrowError::ParseError(format!("Invalid decimal: {:?}", s));
+
+        let (integer, fraction) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+        let digits = format!("{}{}", integer, fraction);
+        let unsigned = digits.trim_start_matches(['-', '+']);
+        if unsigned.is_empty() || !unsigned.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let scale = i8::try_from(fraction.len()).map_err(|_| invalid())?;
+        let value = digits.parse::<i128>().map_err(|_| invalid())?;
+        Ok(Self::new(value, scale))
+    }
+}
+
+/// Converts decimals from Arrow Decimal128 arrays.
+///
+/// The scale of each value is taken from the array's data type.
+/// Returns `None` for null values in the array.
+impl FromArrow for Decimal {
+    type Column<'a> = &'a Decimal128Array;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        downcast_array(array, &Self::array_type())
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        if column.is_null(index) {
+            None
+        } else {
+            Some(Decimal::new(column.value(index), column.scale()))
+        }
+    }
+
+    fn array_type() -> DataType {
+        DataType::Decimal128(DECIMAL128_MAX_PRECISION, DECIMAL_DEFAULT_SCALE)
+    }
+}
+
+/// Converts decimals to Arrow Decimal128 arrays.
+///
+/// Without an output type, all values are brought to the largest scale among
+/// them with the maximum precision of 38 digits. With an output type, every value
+/// is rescaled to the requested `DECIMAL(p,s)`.
+///
+/// # Panics
+///
+/// `to_array` panics if the values cannot share a common scale within 128 bits.
+/// The processor reports this as an error instead.
+impl ToArrow for Decimal {
+    type ArrayType = Decimal128Array;
+    type Builder = DecimalOutputBuilder;
+
+    fn data_type() -> DataType {
+        DataType::Decimal128(DECIMAL128_MAX_PRECISION, DECIMAL_DEFAULT_SCALE)
+    }
+
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        match data_type {
+            Some(data_type) => DecimalOutputBuilder::rescaled(capacity, data_type),
+            None => Ok(DecimalOutputBuilder::widest(capacity)),
+        }
+    }
+}
+
+/// Output builder for [`Decimal`] values.
+///
+/// Values are rescaled to the requested output type as they are appended.
+/// Without one, they are brought to the largest scale appended so far, and the
+/// values written before a larger scale arrives are rescaled in place.
+pub struct DecimalOutputBuilder(DecimalValues);
+
+enum DecimalValues {
+    Rescaled {
+        values: Decimal128Builder,
+        data_type: DataType,
+    },
+    Widest {
+        values: Decimal128Builder,
+        /// The largest scale among the values, once one is appended.
+        scale: Option<i8>,
+    },
+}
+
+impl DecimalOutputBuilder {
+    fn rescaled(capacity: usize, data_type: &DataType) -> Result<Self, ArrowError> {
+        let DataType::Decimal128(precision, scale) = *data_type else {
+            return Err(ArrowError::CastError(format!(
+                "Cannot write decimal values to a {} column",
+                data_type
+            )));
+        };
+        Ok(Self(DecimalValues::Rescaled {
+            values: Decimal128Builder::with_capacity(capacity)
+                .with_precision_and_scale(precision, scale)?,
+            data_type: data_type.clone(),
+        }))
+    }
+
+    fn widest(capacity: usize) -> Self {
+        Self(DecimalValues::Widest {
+            values: Decimal128Builder::with_capacity(capacity),
+            scale: None,
+        })
+    }
+}
+
+/// Rescales `value` to `scale` and checks that it fits into `precision` digits.
+fn fit(value: Decimal, precision: u8, scale: i8) -> Result<i128, ArrowError> {
+    let rescaled = value.rescale(scale)?;
+    if rescaled.precision() > precision {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "Decimal {} overflows {}",
+            value,
+            DataType::Decimal128(precision, scale)
+        )));
+    }
+    Ok(rescaled.value())
+}
+
+impl OutputBuilder<Decimal> for DecimalOutputBuilder {
+    fn append_value(&mut self, value: Decimal) -> Result<(), ArrowError> {
+        match &mut self.0 {
+            DecimalValues::Rescaled { values, data_type } => {
+                let DataType::Decimal128(precision, scale) = *data_type else {
+                    unreachable!("decimal builders are created for Decimal128 columns")
+                };
+                values.append_value(fit(value, precision, scale)?);
+            }
+            DecimalValues::Widest { values, scale } => {
+                let widest = match *scale {
+                    Some(scale) if scale >= value.scale() => scale,
+                    previous => {
+                        // Null slots hold 0, which every scale represents
+                        if let Some(previous) = previous {
+                            for written in values.values_slice_mut() {
+                                *written = fit(
+                                    Decimal::new(*written, previous),
+                                    DECIMAL128_MAX_PRECISION,
+                                    value.scale(),
+                                )?;
+                            }
+                        }
+                        *scale = Some(value.scale());
+                        value.scale()
+                    }
+                };
+                values.append_value(fit(value, DECIMAL128_MAX_PRECISION, widest)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn append_null(&mut self) {
+        match &mut self.0 {
+            DecimalValues::Rescaled { values, .. } | DecimalValues::Widest { values, .. } => {
+                values.append_null()
+            }
+        }
+    }
+
+    fn finish(self) -> Result<ArrayRef, ArrowError> {
+        match self.0 {
+            DecimalValues::Rescaled { mut values, .. } => Ok(Arc::new(values.finish())),
+            DecimalValues::Widest { values, scale } => {
+                let mut values = values
+                    .with_precision_and_scale(DECIMAL128_MAX_PRECISION, scale.unwrap_or(0))?;
+                Ok(Arc::new(values.finish()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_display() {
+        assert_eq!(Decimal::new(12345, 2).to_string(), "123.45");
+        assert_eq!(Decimal::new(-5, 3).to_string(), "-0.005");
+        assert_eq!(Decimal::new(42, 0).to_string(), "42");
+        assert_eq!(Decimal::new(42, -2).to_string(), "4200");
+    }
+
+    #[test]
+    fn test_decimal_from_str() {
+        assert_eq!("123.45".parse::<Decimal>().unwrap(), Decimal::new(12345, 2));
+        assert_eq!("-0.50".parse::<Decimal>().unwrap(), Decimal::new(-50, 2));
+        assert_eq!("7".parse::<Decimal>().unwrap(), Decimal::new(7, 0));
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("abc".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_decimal_rescale() {
+        let value = Decimal::new(12345, 2);
+
+        assert_eq!(value.rescale(4).unwrap(), Decimal::new(1234500, 4));
+        assert_eq!(
+            Decimal::new(1200, 2).rescale(0).unwrap(),
+            Decimal::new(12, 0)
+        );
+        assert!(value.rescale(1).is_err());
+        assert!(Decimal::new(i128::MAX, 0).rescale(1).is_err());
+    }
+
+    #[test]
+    fn test_decimal_round() {
+        assert_eq!(
+            Decimal::new(12345, 2).round(1).unwrap(),
+            Decimal::new(1235, 1)
+        );
+        assert_eq!(
+            Decimal::new(-12345, 2).round(1).unwrap(),
+            Decimal::new(-1235, 1)
+        );
+        assert_eq!(
+            Decimal::new(12344, 2).round(1).unwrap(),
+            Decimal::new(1234, 1)
+        );
+    }
+
+    #[test]
+    fn test_decimal_from_arrow() {
+        let array = Decimal128Array::from(vec![Some(12345), None])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+
+        assert_eq!(Decimal::from_array(&array, 0), Some(Decimal::new(12345, 2)));
+        assert_eq!(Decimal::from_array(&array, 1), None);
+    }
+
+    #[test]
+    fn test_decimal_to_arrow_with_output_type() {
+        let values = vec![Some(Decimal::new(15, 1)), None, Some(Decimal::new(2, 0))];
+        let array = Decimal::to_array_as(values, &DataType::Decimal128(10, 2)).unwrap();
+        let decimal_array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+
+        assert_eq!(decimal_array.data_type(), &DataType::Decimal128(10, 2));
+        assert_eq!(decimal_array.value(0), 150);
+        assert!(decimal_array.is_null(1));
+        assert_eq!(decimal_array.value(2), 200);
+    }
+
+    #[test]
+    fn test_decimal_to_arrow_rejects_lossy_values() {
+        let lossy = vec![Some(Decimal::new(12345, 3))];
+        assert!(Decimal::to_array_as(lossy, &DataType::Decimal128(10, 2)).is_err());
+
+        let overflow = vec![Some(Decimal::new(123456, 0))];
+        assert!(Decimal::to_array_as(overflow, &DataType::Decimal128(5, 0)).is_err());
+    }
+
+    #[test]
+    fn test_decimal_builder_rejects_overflow_on_append() {
+        let mut builder = Decimal::builder(2, Some(&DataType::Decimal128(5, 2))).unwrap();
+        builder.append_value(Decimal::new(12345, 2)).unwrap();
+
+        let error = builder.append_value(Decimal::new(1234567, 2)).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Decimal 12345.67 overflows Decimal128(5, 2)"));
+    }
+
+    #[test]
+    fn test_decimal_to_arrow_uses_common_scale() {
+        let values = vec![Some(Decimal::new(1, 0)), Some(Decimal::new(25, 1))];
+        let array = Decimal::to_array(values);
+        let decimal_array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+
+        assert_eq!(decimal_array.scale(), 1);
+        assert_eq!(decimal_array.value(0), 10);
+        assert_eq!(decimal_array.value(1), 25);
+    }
+
+    #[test]
+    fn test_decimal_builder_rescales_written_values() {
+        let mut builder = Decimal::builder(3, None).unwrap();
+        builder.append_value(Decimal::new(15, 1)).unwrap();
+        builder.append_null();
+        builder.append_value(Decimal::new(-125, 3)).unwrap();
+        let array = builder.finish().unwrap();
+        let decimal_array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+
+        assert_eq!(decimal_array.scale(), 3);
+        assert_eq!(decimal_array.value(0), 1500);
+        assert!(decimal_array.is_null(1));
+        assert_eq!(decimal_array.value(2), -125);
+
+        let mut builder = Decimal::builder(2, None).unwrap();
+        builder
+            .append_value(Decimal::new(10i128.pow(28), 0))
+            .unwrap();
+        let error = builder.append_value(Decimal::new(1, 10)).unwrap_err();
+        assert!(error.to_string().contains("overflows Decimal128(38, 10)"));
+    }
+}
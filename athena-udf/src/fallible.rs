@@ -0,0 +1,340 @@
+use crate::arrow_conversions::ToArrow;
+use arrow::array::ArrayRef;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use std::any::Any;
+use std::convert::Infallible;
+use std::fmt::Display;
+
+/// The return type of a UDF: either a plain value or a `Result`.
+///
+/// Plain [`ToArrow`] values always succeed. A UDF returning `Result<T, E>` can fail
+/// for individual rows; what happens then is decided by the processor's
+/// [`ErrorPolicy`].
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{Array, Int64Array, StringArray};
+/// # use arrow::datatypes::{DataType, Field, Schema};
+/// # use arrow::record_batch::RecordBatch;
+/// # use std::num::ParseIntError;
+/// # use std::sync::Arc;
+/// # use athena_udf::{ErrorPolicy, UDFProcessor};
+/// let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+/// let array = StringArray::from(vec!["42", "oops"]);
+/// let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+///
+/// let parse = |s: &str| s.parse::<i64>();
+///
+/// let error = UDFProcessor::new(&batch)
+///     .with_method_name("parse_int")
+///     .process_unary::<&str, Result<i64, ParseIntError>, _>("n", parse)
+///     .unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "parse_int failed at row 1 with input (oops): invalid digit found in string"
+/// );
+///
+/// let output_batch = UDFProcessor::new(&batch)
+///     .with_error_policy(ErrorPolicy::Null)
+///     .process_unary::<&str, Result<i64, ParseIntError>, _>("n", parse)
+///     .unwrap();
+/// let output_array = output_batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+/// assert_eq!(output_array.value(0), 42);
+/// assert!(output_array.is_null(1));
+/// ```
+pub trait UdfOutput {
+    /// The value written to the output column.
+    type Value: ToArrow;
+    /// The error reported for a failed row.
+    type Error: Display;
+
+    fn into_result(self) -> Result<Self::Value, Self::Error>;
+}
+
+impl<T: ToArrow> UdfOutput for T {
+    type Value = T;
+    type Error = Infallible;
+
+    fn into_result(self) -> Result<Self::Value, Self::Error> {
+        Ok(self)
+    }
+}
+
+impl<T: ToArrow, E: Display> UdfOutput for Result<T, E> {
+    type Value = T;
+    type Error = E;
+
+    fn into_result(self) -> Result<Self::Value, Self::Error> {
+        self
+    }
+}
+
+/// What the processor does when a fallible UDF returns an error for a row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Fails the whole batch with the first error.
+    #[default]
+    Fail,
+    /// Writes NULL for failed rows and keeps going.
+    Null,
+    /// Processes every row, then fails the batch if any row failed.
+    ///
+    /// Unlike [`ErrorPolicy::Fail`], the error reports how many rows failed and
+    /// lists the messages of at most this many of them.
+    FailListing(usize),
+}
+
+/// Tracks row errors of one batch according to an [`ErrorPolicy`].
+pub(crate) struct RowErrors<'a> {
+    policy: ErrorPolicy,
+    method_name: &'a str,
+    messages: Vec<String>,
+    count: usize,
+}
+
+impl<'a> RowErrors<'a> {
+    pub(crate) fn new(policy: ErrorPolicy, method_name: &'a str) -> Self {
+        Self {
+            policy,
+            method_name,
+            messages: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Records a failed row, returning the error right away under [`ErrorPolicy::Fail`].
+    ///
+    /// `row_idx` finds the row in the batch and `input` renders its input values.
+    /// Both are only called when the error is reported.
+    pub(crate) fn record(
+        &mut self,
+        row_idx: impl FnOnce() -> usize,
+        input: impl FnOnce() -> String,
+        error: impl Display,
+    ) -> Result<(), String> {
+        self.count += 1;
+        let describe = || {
+            format!(
+                "{} failed at row {} with input ({}): {}",
+                self.method_name,
+                row_idx(),
+                input(),
+                error
+            )
+        };
+
+        match self.policy {
+            ErrorPolicy::Fail => Err(describe()),
+            ErrorPolicy::Null => Ok(()),
+            ErrorPolicy::FailListing(max) => {
+                if self.messages.len() < max {
+                    let message = describe();
+                    self.messages.push(message);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Describes a panic of the UDF for a row.
+    ///
+    /// Panics are bugs rather than bad input, so they fail the batch under every
+    /// error policy.
+    pub(crate) fn panicked(
+        &self,
+        row_idx: impl FnOnce() -> usize,
+        input: impl FnOnce() -> String,
+        payload: Box<dyn Any + Send>,
+    ) -> String {
+        format!(
+            "{} panicked at row {} with input ({}): {}",
+            self.method_name,
+            row_idx(),
+            input(),
+            panic_message(payload.as_ref())
+        )
+    }
+
+    /// Returns the collected errors once all rows are processed.
+    pub(crate) fn finish(self) -> Result<(), FailedRows> {
+        if self.count == 0 {
+            return Ok(());
+        }
+
+        match self.policy {
+            ErrorPolicy::FailListing(max) => Err(FailedRows {
+                method_name: self.method_name.to_string(),
+                count: self.count,
+                messages: self.messages,
+                max,
+            }),
+            _ => {
+                log::warn!(
+                    "{} returned NULL for {} failed rows",
+                    self.method_name,
+                    self.count
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The error of a batch processed under [`ErrorPolicy::FailListing`].
+///
+/// Keeps the messages apart so that the errors of the chunks of a parallel
+/// batch can be reported together.
+#[derive(Debug)]
+pub(crate) struct FailedRows {
+    method_name: String,
+    count: usize,
+    messages: Vec<String>,
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    max: usize,
+}
+
+impl FailedRows {
+    /// Adds the failed rows of a later chunk of the same batch.
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    pub(crate) fn merge(&mut self, other: FailedRows) {
+        self.count += other.count;
+        let room = self.max.saturating_sub(self.messages.len());
+        self.messages.extend(other.messages.into_iter().take(room));
+    }
+}
+
+impl Display for FailedRows {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} failed for {} rows", self.method_name, self.count)?;
+        for message in &self.messages {
+            write!(f, "\n  {}", message)?;
+        }
+        if self.count > self.messages.len() {
+            write!(f, "\n  ... and {} more", self.count - self.messages.len())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FailedRows {}
+
+/// Returns the message a panic was raised with.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => payload
+            .downcast_ref::<String>()
+            .map_or("unknown panic payload", String::as_str),
+    }
+}
+
+/// Renders the input values of one row as comma-separated text.
+pub(crate) fn render_row(columns: &[ArrayRef], row_idx: usize) -> String {
+    let options = FormatOptions::default().with_null("NULL");
+    columns
+        .iter()
+        .map(
+            |column| match ArrayFormatter::try_new(column.as_ref(), &options) {
+                Ok(formatter) => formatter.value(row_idx).to_string(),
+                Err(_) => "?".to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use std::sync::Arc;
+
+    fn columns() -> Vec<ArrayRef> {
+        vec![
+            Arc::new(StringArray::from(vec![Some("a"), None])),
+            Arc::new(Int64Array::from(vec![1, 2])),
+        ]
+    }
+
+    #[test]
+    fn test_fail_policy_returns_first_error() {
+        let columns = columns();
+        let mut errors = RowErrors::new(ErrorPolicy::Fail, "f");
+
+        assert_eq!(
+            errors
+                .record(|| 1, || render_row(&columns, 1), "boom")
+                .unwrap_err(),
+            "f failed at row 1 with input (NULL, 2): boom"
+        );
+    }
+
+    #[test]
+    fn test_null_policy_ignores_errors() {
+        let mut errors = RowErrors::new(ErrorPolicy::Null, "f");
+
+        // Neither the row nor its input is looked up for an error that is not reported
+        assert!(errors
+            .record(|| unreachable!(), || unreachable!(), "boom")
+            .is_ok());
+        assert!(errors.finish().is_ok());
+    }
+
+    #[test]
+    fn test_fail_listing_policy_lists_errors() {
+        let columns = columns();
+        let mut errors = RowErrors::new(ErrorPolicy::FailListing(1), "f");
+
+        assert!(errors
+            .record(|| 0, || render_row(&columns, 0), "first")
+            .is_ok());
+        assert!(errors
+            .record(|| 1, || render_row(&columns, 1), "second")
+            .is_ok());
+        let message = errors.finish().unwrap_err().to_string();
+
+        assert!(message.starts_with("f failed for 2 rows"));
+        assert!(message.contains("row 0 with input (a, 1): first"));
+        assert!(!message.contains("second"));
+        assert!(message.ends_with("... and 1 more"));
+    }
+
+    #[test]
+    fn test_fail_listing_merges_chunks() {
+        let columns = columns();
+        let mut first = RowErrors::new(ErrorPolicy::FailListing(2), "f");
+        assert!(first
+            .record(|| 0, || render_row(&columns, 0), "first")
+            .is_ok());
+        let mut second = RowErrors::new(ErrorPolicy::FailListing(2), "f");
+        assert!(second
+            .record(|| 1, || render_row(&columns, 1), "second")
+            .is_ok());
+        assert!(second
+            .record(|| 1, || render_row(&columns, 1), "third")
+            .is_ok());
+
+        let mut failed = first.finish().unwrap_err();
+        failed.merge(second.finish().unwrap_err());
+        let message = failed.to_string();
+
+        assert!(message.starts_with("f failed for 3 rows"));
+        assert!(message.contains("first"));
+        assert!(message.contains("second"));
+        assert!(!message.contains("third"));
+        assert!(message.ends_with("... and 1 more"));
+    }
+
+    #[test]
+    fn test_panicked_describes_row_and_message() {
+        let columns = columns();
+        let errors = RowErrors::new(ErrorPolicy::Null, "f");
+        let payload = std::panic::catch_unwind(|| panic!("bad {}", "value")).unwrap_err();
+
+        assert_eq!(
+            errors.panicked(|| 1, || render_row(&columns, 1), payload),
+            "f panicked at row 1 with input (NULL, 2): bad value"
+        );
+    }
+}
@@ -1,21 +1,55 @@
 pub mod arrow_conversions;
+pub mod coercion;
+pub mod context;
+pub mod decimal;
+#[doc(hidden)]
+pub mod fallible;
+pub mod memoize;
+pub mod nested;
+pub mod null_handling;
+#[cfg(feature = "rayon")]
+pub mod parallel;
 pub mod process_macro;
 pub mod register_macro;
 pub mod request;
 pub mod response;
 pub mod serde_base64;
 pub mod serialization;
+pub mod signature;
+pub mod state;
+#[cfg(feature = "chrono")]
+pub mod temporal;
 
+// Lets the derive macros refer to `::athena_udf` from inside this crate too.
+extern crate self as athena_udf;
+
+use arrow::datatypes::Field;
 use arrow::record_batch::RecordBatch;
-pub use arrow_conversions::{FromArrow, ToArrow};
+pub use arrow_conversions::{FromArrow, FromArrowRef, OutputBuilder, ToArrow};
+pub use coercion::Coercion;
+pub use context::UdfContext;
+pub use decimal::Decimal;
+pub use fallible::{ErrorPolicy, UdfOutput};
+pub use memoize::Memoization;
+pub use null_handling::NullHandling;
+#[cfg(feature = "rayon")]
+pub use parallel::ParallelProcessor;
 pub use process_macro::UDFProcessor;
 pub use request::{AthenaUDFRequest, Identity, InputRecords, OutputSchemaWrapper, PingRequest};
 pub use response::{AthenaResponse, AthenaUDFResponse, OutputRecords, PingResponse};
+pub use state::{LazyState, State};
 
 pub use lambda_runtime::{run, service_fn, LambdaEvent};
 pub use serde::{Deserialize, Serialize};
 pub use serde_json::Value;
 
+pub use arrow;
+#[cfg(feature = "derive")]
+pub use athena_udf_derive::{FromArrow, ToArrow};
+
+#[cfg(feature = "chrono")]
+pub use chrono;
+
 // Re-export for backwards compatibility
 use lambda_runtime::Error;
 
@@ -27,16 +61,24 @@ pub fn wrap_response(response: AthenaResponse, is_http: bool) -> Result<Value, E
 ///
 /// Takes the input batch, method name, and output column name,
 /// and returns a processed RecordBatch.
+#[deprecated(note = "use `UDFFieldHandler`, which receives the requested output field")]
 pub type UDFHandler = fn(&RecordBatch, &str, &str) -> Result<RecordBatch, Error>;
 
+/// A handler function type for processing UDF requests.
+///
+/// Takes the input batch, method name, and the output field requested by Athena,
+/// and returns a processed RecordBatch.
+pub type UDFFieldHandler = fn(&RecordBatch, &str, &Field) -> Result<RecordBatch, Error>;
+
 /// Main entry point for Athena UDF Lambda handlers.
 ///
 /// Automatically handles both PingRequest and UserDefinedFunctionRequest,
-/// routing UDF calls to the provided handler function.
+/// routing UDF calls to the provided handler function with the output column name.
 ///
 /// # Examples
 ///
 /// ```no_run
+/// # #![allow(deprecated)]
 /// use athena_udf::*;
 /// use lambda_runtime::{service_fn, run, Error};
 ///
@@ -54,6 +96,9 @@ pub type UDFHandler = fn(&RecordBatch, &str, &str) -> Result<RecordBatch, Error>
 ///     }).await
 /// }
 /// ```
+#[deprecated(
+    note = "use `handle_athena_request_with_field`, which passes the requested output field"
+)]
 pub async fn handle_athena_request<F>(
     event: LambdaEvent<Value>,
     udf_handler: F,
@@ -61,7 +106,142 @@ pub async fn handle_athena_request<F>(
 where
     F: Fn(&RecordBatch, &str, &str) -> Result<RecordBatch, Error>,
 {
-    let (actual_payload, is_http) = AthenaResponse::parse_request(event.payload)?;
+    handle_athena_request_with_field(event, |input_batch, method_name, output_field| {
+        udf_handler(input_batch, method_name, output_field.name())
+    })
+    .await
+}
+
+/// Main entry point for Athena UDF Lambda handlers.
+///
+/// Automatically handles both PingRequest and UserDefinedFunctionRequest,
+/// routing UDF calls to the provided handler function with the output field
+/// requested by Athena, so results can follow its type and nullability.
+///
+/// # Examples
+///
+/// ```no_run
+/// use athena_udf::*;
+/// use lambda_runtime::{service_fn, run, Error};
+///
+/// fn string_reverse(s: String) -> String {
+///     s.chars().rev().collect()
+/// }
+///
+/// async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
+///     handle_athena_request_with_field(event, |input_batch, method_name, output_field| {
+///         match method_name {
+///             "string_reverse" => UDFProcessor::new(input_batch)
+///                 .with_output_field(output_field)
+///                 .process_unary::<String, String, _>(output_field.name(), string_reverse),
+///             _ => Err(format!("Unknown function: {}", method_name).into()),
+///         }
+///     }).await
+/// }
+/// ```
+pub async fn handle_athena_request_with_field<F>(
+    event: LambdaEvent<Value>,
+    udf_handler: F,
+) -> Result<Value, Error>
+where
+    F: Fn(&RecordBatch, &str, &Field) -> Result<RecordBatch, Error>,
+{
+    handle_athena_request_with_context(event, |input_batch, context, output_field| {
+        udf_handler(input_batch, &context.method_name, output_field)
+    })
+    .await
+}
+
+/// Entry point for Athena UDF Lambda handlers that need the request context.
+///
+/// Works like [`handle_athena_request_with_field`], but the handler receives a [`UdfContext`]
+/// with the caller's identity, the method name and the Lambda invocation context
+/// instead of just the method name.
+///
+/// # Examples
+///
+/// ```no_run
+/// use athena_udf::*;
+/// use lambda_runtime::Error;
+///
+/// fn string_reverse(s: String) -> String {
+///     s.chars().rev().collect()
+/// }
+///
+/// async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
+///     handle_athena_request_with_context(event, |input_batch, context, output_field| {
+///         log::info!(
+///             "{} called by {:?} in request {}",
+///             context.method_name,
+///             context.identity.arn,
+///             context.lambda.request_id
+///         );
+///         match context.method_name.as_str() {
+///             "string_reverse" => UDFProcessor::new(input_batch)
+///                 .process_unary::<String, String, _>(output_field.name(), string_reverse),
+///             _ => Err(format!("Unknown function: {}", context.method_name).into()),
+///         }
+///     }).await
+/// }
+/// ```
+pub async fn handle_athena_request_with_context<F>(
+    event: LambdaEvent<Value>,
+    udf_handler: F,
+) -> Result<Value, Error>
+where
+    F: Fn(&RecordBatch, &UdfContext, &Field) -> Result<RecordBatch, Error>,
+{
+    // The handler runs inside the future, so a panic is caught and reported
+    // like one of an async handler
+    let udf_handler = &udf_handler;
+    handle_athena_request_async(event, |input_batch, context, output_field| async move {
+        udf_handler(&input_batch, &context, &output_field)
+    })
+    .await
+}
+
+/// Entry point for Athena UDF Lambda handlers with async UDFs.
+///
+/// Works like [`handle_athena_request_with_context`], but the handler returns a
+/// future, so it can await async UDFs such as
+/// [`UDFProcessor::process_unary_async`]. It receives the input batch, the
+/// [`UdfContext`] and the output field by value, so the future can own them.
+///
+/// # Examples
+///
+/// ```no_run
+/// use athena_udf::*;
+/// use lambda_runtime::Error;
+///
+/// async fn lookup_name(id: i64) -> String {
+///     // e.g. a request to a sidecar
+///     format!("user-{}", id)
+/// }
+///
+/// async fn function_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
+///     handle_athena_request_async(event, |input_batch, context, output_field| async move {
+///         match context.method_name.as_str() {
+///             "lookup_name" => {
+///                 UDFProcessor::new(&input_batch)
+///                     .with_concurrency(8)
+///                     .process_unary_async::<i64, String, _, _>(output_field.name(), lookup_name)
+///                     .await
+///             }
+///             _ => Err(format!("Unknown function: {}", context.method_name).into()),
+///         }
+///     }).await
+/// }
+/// ```
+pub async fn handle_athena_request_async<F, Fut>(
+    event: LambdaEvent<Value>,
+    udf_handler: F,
+) -> Result<Value, Error>
+where
+    F: Fn(RecordBatch, UdfContext, Field) -> Fut,
+    Fut: std::future::Future<Output = Result<RecordBatch, Error>>,
+{
+    let (payload, lambda_context) = event.into_parts();
+    let (actual_payload, is_http) = AthenaResponse::parse_request(payload)?;
 
     let request_type = actual_payload
         .get("@type")
@@ -75,10 +255,63 @@ where
         }
         "UserDefinedFunctionRequest" => {
             let udf_req: AthenaUDFRequest = serde_json::from_value(actual_payload)?;
-            udf_req.process_with(&udf_handler)?
+            udf_req
+                .process_with_async(lambda_context, udf_handler)
+                .await?
         }
         _ => return Err(format!("Unknown request type: {}", request_type).into()),
     };
 
     response.wrap_response(is_http)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Schema};
+    use base64::{engine::general_purpose, Engine as _};
+    use std::sync::Arc;
+
+    fn udf_request(method_name: &str) -> Value {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+        let array = Int64Array::from(vec![1, 2]);
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+        let output_schema = Arc::new(Schema::new(vec![Field::new(
+            "output",
+            DataType::Int64,
+            true,
+        )]));
+
+        let encode = |bytes: Vec<u8>| general_purpose::STANDARD.encode(bytes);
+        serde_json::json!({
+            "@type": "UserDefinedFunctionRequest",
+            "identity": {},
+            "inputRecords": {
+                "aId": "a1",
+                "schema": encode(serialization::serialize_schema(&schema).unwrap()),
+                "records": encode(serialization::serialize_batches(&[batch]).unwrap()),
+            },
+            "outputSchema": {
+                "schema": encode(serialization::serialize_schema(&output_schema).unwrap()),
+            },
+            "methodName": method_name,
+            "functionType": "SCALAR",
+        })
+    }
+
+    fn panicking_handler(_: &RecordBatch, _: &str, _: &str) -> Result<RecordBatch, Error> {
+        panic!("handler bug")
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_handle_athena_request_reports_handler_panic() {
+        let event = LambdaEvent::new(udf_request("boom"), lambda_runtime::Context::default());
+
+        let error = handle_athena_request(event, panicking_handler)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "boom panicked: handler bug");
+    }
+}
@@ -0,0 +1,294 @@
+use crate::fallible::ErrorPolicy;
+use crate::request::Identity;
+use arrow::array::{Array, ArrayRef, UInt32Array};
+use arrow::compute::{cast, take};
+use arrow::datatypes::DataType;
+use arrow::error::ArrowError;
+use arrow::row::{OwnedRow, RowConverter, SortField};
+use lru::LruCache;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// How the processor reuses results of a UDF for repeated inputs.
+///
+/// Memoization is only correct for deterministic UDFs, i.e. functions without
+/// side effects that return the same result for the same inputs. Inputs are
+/// compared by their Arrow values, so any input type can be memoized.
+///
+/// Results of a UDF that reads the request context are only reused for the same
+/// identity, see [`UDFProcessor::with_context`](crate::UDFProcessor::with_context).
+/// Under [`ErrorPolicy::Null`], NULL results are not kept across batches, since
+/// they may stand for failed rows.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{Array, StringArray};
+/// # use arrow::datatypes::{DataType, Field, Schema};
+/// # use arrow::record_batch::RecordBatch;
+/// # use std::cell::Cell;
+/// # use std::sync::Arc;
+/// # use athena_udf::{Memoization, UDFProcessor};
+/// let schema = Arc::new(Schema::new(vec![Field::new("country", DataType::Utf8, true)]));
+/// let array = StringArray::from(vec!["DE", "FR", "DE", "DE"]);
+/// let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+///
+/// let calls = Cell::new(0);
+/// let output_batch = UDFProcessor::new(&batch)
+///     .with_memoization(Memoization::Batch)
+///     .process_unary::<&str, String, _>("lower", |s| {
+///         calls.set(calls.get() + 1);
+///         s.to_lowercase()
+///     })
+///     .unwrap();
+///
+/// let output_array = output_batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+/// assert_eq!(output_array.value(3), "de");
+/// assert_eq!(calls.get(), 2);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Memoization {
+    /// Calls the UDF for every row.
+    #[default]
+    Off,
+    /// Calls the UDF once per distinct input tuple of a batch.
+    Batch,
+    /// Like `Batch`, and keeps up to this many results in an LRU cache that
+    /// lives as long as the Lambda container, so warm invocations reuse them.
+    ///
+    /// The cache is kept per method name, so the processor must be given one
+    /// with [`UDFProcessor::with_method_name`](crate::UDFProcessor::with_method_name).
+    /// The registration macros do this.
+    Lru(usize),
+}
+
+/// The results of one UDF kept across batches, with inputs and results encoded
+/// as Arrow rows.
+struct ResultCache {
+    entries: LruCache<OwnedRow, OwnedRow>,
+    /// Encodes the results, created for the output type of the first batch.
+    converter: Option<(DataType, RowConverter)>,
+}
+
+/// Identifies the cached results of one UDF for one set of input and output
+/// types, and for one identity if the UDF reads the request context.
+#[derive(PartialEq, Eq, Hash)]
+struct CacheKey {
+    method_name: String,
+    input_types: Vec<DataType>,
+    output_type: Option<DataType>,
+    identity: Option<Identity>,
+}
+
+type SharedCache = Arc<Mutex<ResultCache>>;
+
+static RESULT_CACHES: OnceLock<Mutex<HashMap<CacheKey, SharedCache>>> = OnceLock::new();
+
+/// Returns the result cache for `key`, creating it or applying a new capacity.
+fn result_cache(key: CacheKey, capacity: NonZeroUsize) -> SharedCache {
+    let mut caches = RESULT_CACHES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let cache = caches.entry(key).or_insert_with(|| {
+        Arc::new(Mutex::new(ResultCache {
+            entries: LruCache::new(capacity),
+            converter: None,
+        }))
+    });
+    {
+        let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if cache.entries.cap() != capacity {
+            cache.entries.resize(capacity);
+        }
+    }
+    cache.clone()
+}
+
+/// Where the result of a distinct input row comes from.
+enum Source {
+    /// The result was found in the result cache.
+    Cached(OwnedRow),
+    /// The UDF is evaluated for this input; the value is its evaluated row.
+    Evaluated(usize),
+}
+
+/// The distinct input rows of a batch that the UDF has to be evaluated for.
+pub(crate) struct Distinct {
+    /// The input columns, reduced to the rows to evaluate.
+    pub(crate) columns: Vec<ArrayRef>,
+    /// The number of rows to evaluate.
+    pub(crate) num_rows: usize,
+    /// The distinct row of every input row.
+    pub(crate) keys: ArrayRef,
+    /// Results of distinct rows taken from the result cache, if one is used.
+    pub(crate) cached: Option<CachedResults>,
+}
+
+/// Results of distinct rows served by the result cache of [`Memoization::Lru`].
+pub(crate) struct CachedResults {
+    cache: SharedCache,
+    /// The source of every distinct row.
+    sources: Vec<Source>,
+    /// The distinct row and the cache key of every evaluated row.
+    evaluated: Vec<(usize, OwnedRow)>,
+    /// Whether NULL results are cached. Not under [`ErrorPolicy::Null`], where
+    /// a NULL may stand for a failed row that has to be evaluated again.
+    cache_nulls: bool,
+}
+
+impl CachedResults {
+    /// Returns the distinct row of an evaluated row.
+    pub(crate) fn distinct_row(&self, row_idx: usize) -> usize {
+        self.evaluated[row_idx].0
+    }
+
+    /// Caches the results of the evaluated rows and returns the results of all
+    /// distinct rows.
+    pub(crate) fn merge(&self, output: ArrayRef) -> Result<ArrayRef, ArrowError> {
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        let ResultCache { entries, converter } = &mut *cache;
+
+        let (output_type, converter) = match converter {
+            Some(converter) => converter,
+            None => {
+                let fields = vec![SortField::new(output.data_type().clone())];
+                if !RowConverter::supports_fields(&fields) {
+                    // Nothing can be cached, so every distinct row was evaluated
+                    return Ok(output);
+                }
+                converter.insert((output.data_type().clone(), RowConverter::new(fields)?))
+            }
+        };
+        let output = if output.data_type() == output_type {
+            output
+        } else {
+            cast(&output, output_type)?
+        };
+
+        let evaluated = converter.convert_columns(std::slice::from_ref(&output))?;
+        for (row_idx, (_, input)) in self.evaluated.iter().enumerate() {
+            if self.cache_nulls || output.is_valid(row_idx) {
+                entries.put(input.clone(), evaluated.row(row_idx).owned());
+            }
+        }
+
+        let rows = self.sources.iter().map(|source| match source {
+            Source::Evaluated(row_idx) => evaluated.row(*row_idx),
+            Source::Cached(value) => value.row(),
+        });
+        let mut columns = converter.convert_rows(rows)?;
+        Ok(columns.remove(0))
+    }
+}
+
+/// Finds the distinct rows of `columns` and looks them up in the result cache.
+///
+/// Returns `None` if memoization is off or the input types cannot be compared,
+/// in which case the UDF is evaluated for every row.
+pub(crate) fn distinct(
+    columns: &[ArrayRef],
+    memoization: Memoization,
+    method_name: &str,
+    output_type: Option<&DataType>,
+    error_policy: ErrorPolicy,
+    identity: Option<&Identity>,
+) -> Result<Option<Distinct>, ArrowError> {
+    let capacity = match memoization {
+        Memoization::Off => return Ok(None),
+        Memoization::Batch => None,
+        Memoization::Lru(capacity) => NonZeroUsize::new(capacity),
+    };
+    let Some(num_rows) = columns.first().map(|column| column.len()) else {
+        return Ok(None);
+    };
+
+    let fields = columns
+        .iter()
+        .map(|column| SortField::new(column.data_type().clone()))
+        .collect::<Vec<_>>();
+    if !RowConverter::supports_fields(&fields) {
+        log::debug!("{} inputs cannot be memoized", method_name);
+        return Ok(None);
+    }
+    let rows = RowConverter::new(fields)?.convert_columns(columns)?;
+
+    let mut distinct_rows = HashMap::new();
+    let mut first_rows = Vec::new();
+    let keys = rows
+        .iter()
+        .enumerate()
+        .map(|(row_idx, row)| {
+            *distinct_rows.entry(row).or_insert_with(|| {
+                first_rows.push(row_idx as u32);
+                (first_rows.len() - 1) as u32
+            })
+        })
+        .collect::<UInt32Array>();
+
+    let (evaluated_rows, cached) = match capacity {
+        Some(capacity) => {
+            let cache = result_cache(
+                CacheKey {
+                    method_name: method_name.to_string(),
+                    input_types: columns.iter().map(|c| c.data_type().clone()).collect(),
+                    output_type: output_type.cloned(),
+                    identity: identity.cloned(),
+                },
+                capacity,
+            );
+
+            let mut sources = Vec::with_capacity(first_rows.len());
+            let mut evaluated = Vec::new();
+            let mut evaluated_rows = Vec::new();
+            {
+                let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+                for (distinct_row, &row_idx) in first_rows.iter().enumerate() {
+                    let row = rows.row(row_idx as usize).owned();
+                    match cache.entries.get(&row) {
+                        Some(value) => sources.push(Source::Cached(value.clone())),
+                        None => {
+                            sources.push(Source::Evaluated(evaluated.len()));
+                            evaluated.push((distinct_row, row));
+                            evaluated_rows.push(row_idx);
+                        }
+                    }
+                }
+            }
+
+            let cached = CachedResults {
+                cache,
+                sources,
+                evaluated,
+                cache_nulls: error_policy != ErrorPolicy::Null,
+            };
+            (evaluated_rows, Some(cached))
+        }
+        None if first_rows.len() == num_rows => {
+            log::debug!("{} memoization: 0 hits, {} misses", method_name, num_rows);
+            return Ok(None);
+        }
+        None => (first_rows, None),
+    };
+
+    log::debug!(
+        "{} memoization: {} hits, {} misses",
+        method_name,
+        num_rows - evaluated_rows.len(),
+        evaluated_rows.len()
+    );
+
+    let indices = UInt32Array::from(evaluated_rows);
+    let columns = columns
+        .iter()
+        .map(|column| take(column.as_ref(), &indices, None))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some(Distinct {
+        columns,
+        num_rows: indices.len(),
+        keys: Arc::new(keys),
+        cached,
+    }))
+}
@@ -0,0 +1,966 @@
+use crate::arrow_conversions::{FromArrow, OutputBuilder, ToArrow};
+use arrow::array::{
+    Array, ArrayRef, AsArray, GenericListArray, ListArray, MapArray, OffsetSizeTrait, StructArray,
+    UInt32Array,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow::compute::{sort_to_indices, take};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use arrow::error::ArrowError;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Column view over an Arrow `List` or `LargeList` array.
+///
+/// Holds the list offsets and validity together with a column view over the
+/// child values, so elements are read through the element type's own
+/// [`FromArrow`] implementation.
+pub struct ListColumn<'a, T: FromArrow> {
+    offsets: ListOffsets<'a>,
+    nulls: Option<&'a NullBuffer>,
+    values: T::Column<'a>,
+}
+
+enum ListOffsets<'a> {
+    Small(&'a [i32]),
+    Large(&'a [i64]),
+}
+
+impl<'a, T: FromArrow> ListColumn<'a, T> {
+    fn try_new(array: &'a dyn Array) -> Result<Self, ArrowError> {
+        let (offsets, nulls, values) = match array.data_type() {
+            DataType::List(_) => {
+                let list = array.as_list::<i32>();
+                (
+                    ListOffsets::Small(list.value_offsets()),
+                    list.nulls(),
+                    list.values(),
+                )
+            }
+            DataType::LargeList(_) => {
+                let list = array.as_list::<i64>();
+                (
+                    ListOffsets::Large(list.value_offsets()),
+                    list.nulls(),
+                    list.values(),
+                )
+            }
+            other => {
+                return Err(ArrowError::CastError(format!(
+                    "expected List but got {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            offsets,
+            nulls,
+            values: T::column(values.as_ref())?,
+        })
+    }
+
+    fn range(&self, index: usize) -> Option<Range<usize>> {
+        if self.nulls.is_some_and(|nulls| nulls.is_null(index)) {
+            return None;
+        }
+
+        Some(match self.offsets {
+            ListOffsets::Small(offsets) => offsets[index] as usize..offsets[index + 1] as usize,
+            ListOffsets::Large(offsets) => offsets[index] as usize..offsets[index + 1] as usize,
+        })
+    }
+}
+
+fn build_list<O: OffsetSizeTrait>(
+    item_field: FieldRef,
+    lengths: Vec<usize>,
+    validity: Vec<bool>,
+    items: ArrayRef,
+) -> Result<ArrayRef, ArrowError> {
+    let array = GenericListArray::<O>::try_new(
+        item_field,
+        OffsetBuffer::<O>::from_lengths(lengths),
+        items,
+        Some(NullBuffer::from(validity)),
+    )?;
+    Ok(Arc::new(array))
+}
+
+/// Converts Athena `ARRAY<T>` values from Arrow `List` and `LargeList` arrays.
+///
+/// Elements are read with `T`'s [`FromArrow`] implementation, so any supported
+/// element type works, including nested arrays. A list that contains a null
+/// element is treated like a null input for `Vec<T>`; use `Vec<Option<T>>` to
+/// receive null elements.
+///
+/// `Vec<u8>` is not covered by this implementation and keeps mapping to
+/// `VARBINARY`. Athena has no unsigned integer types, so it is never an element type.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::ListArray;
+/// # use arrow::datatypes::Int64Type;
+/// # use athena_udf::arrow_conversions::FromArrow;
+/// let array = ListArray::from_iter_primitive::<Int64Type, _, _>(vec![
+///     Some(vec![Some(1), Some(2)]),
+///     Some(vec![Some(3), None]),
+///     None,
+/// ]);
+///
+/// assert_eq!(Vec::<i64>::from_array(&array, 0), Some(vec![1, 2]));
+/// assert_eq!(Vec::<i64>::from_array(&array, 1), None);
+/// assert_eq!(Vec::<Option<i64>>::from_array(&array, 1), Some(vec![Some(3), None]));
+/// assert_eq!(Vec::<i64>::from_array(&array, 2), None);
+/// ```
+impl<T: FromArrow> FromArrow for Vec<T> {
+    type Column<'a> = ListColumn<'a, T>;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        ListColumn::try_new(array)
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        column
+            .range(index)?
+            .map(|item| T::from_column(&column.values, item))
+            .collect()
+    }
+
+    fn array_type() -> DataType {
+        DataType::List(Arc::new(Field::new_list_field(T::array_type(), true)))
+    }
+}
+
+/// Converts `Vec<T>` values to Arrow `List` arrays for Athena `ARRAY<T>` results.
+///
+/// Elements are written with `T`'s [`ToArrow`] implementation. When the requested
+/// output type is a `List` or `LargeList`, its child field (name and element type)
+/// is reused, so nested decimals or timestamps follow the output schema too.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{Array, AsArray};
+/// # use arrow::datatypes::Int64Type;
+/// # use athena_udf::arrow_conversions::ToArrow;
+/// let array = Vec::<i64>::to_array(vec![Some(vec![1, 2]), None, Some(vec![])]);
+/// let list = array.as_list::<i32>();
+///
+/// assert_eq!(list.value(0).as_primitive::<Int64Type>().values(), &[1, 2]);
+/// assert!(list.is_null(1));
+/// assert_eq!(list.value(2).len(), 0);
+/// ```
+impl<T: ToArrow> ToArrow for Vec<T> {
+    type ArrayType = ListArray;
+    type Builder = ListOutputBuilder<T>;
+
+    fn data_type() -> DataType {
+        DataType::List(Arc::new(Field::new_list_field(T::data_type(), true)))
+    }
+
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        let (item_field, large) = match data_type {
+            None => (Arc::new(Field::new_list_field(T::data_type(), true)), false),
+            Some(DataType::List(field)) => (field.clone(), false),
+            Some(DataType::LargeList(field)) => (field.clone(), true),
+            Some(other) => {
+                return Err(ArrowError::CastError(format!(
+                    "Cannot write array values to a {} column",
+                    other
+                )))
+            }
+        };
+
+        Ok(ListOutputBuilder {
+            items: T::builder(capacity, data_type.map(|_| item_field.data_type()))?,
+            item_field,
+            large,
+            lengths: Vec::with_capacity(capacity),
+            validity: Vec::with_capacity(capacity),
+        })
+    }
+}
+
+/// Output builder for `Vec<T>` values, appending elements to the builder of `T`.
+pub struct ListOutputBuilder<T: ToArrow> {
+    items: T::Builder,
+    item_field: FieldRef,
+    large: bool,
+    lengths: Vec<usize>,
+    validity: Vec<bool>,
+}
+
+impl<T: ToArrow> OutputBuilder<Vec<T>> for ListOutputBuilder<T> {
+    fn append_value(&mut self, value: Vec<T>) -> Result<(), ArrowError> {
+        let len = value.len();
+        value
+            .into_iter()
+            .try_for_each(|item| self.items.append_value(item))?;
+        self.lengths.push(len);
+        self.validity.push(true);
+        Ok(())
+    }
+
+    fn append_null(&mut self) {
+        self.lengths.push(0);
+        self.validity.push(false);
+    }
+
+    fn finish(self) -> Result<ArrayRef, ArrowError> {
+        let items = self.items.finish()?;
+        let item_field = Arc::new(
+            self.item_field
+                .as_ref()
+                .clone()
+                .with_data_type(items.data_type().clone()),
+        );
+
+        if self.large {
+            build_list::<i64>(item_field, self.lengths, self.validity, items)
+        } else {
+            build_list::<i32>(item_field, self.lengths, self.validity, items)
+        }
+    }
+}
+
+/// Column view over an Arrow `Map` array.
+///
+/// Holds the entry offsets and validity together with column views over the
+/// key and value children.
+pub struct MapColumn<'a, K: FromArrow, V: FromArrow> {
+    offsets: &'a [i32],
+    nulls: Option<&'a NullBuffer>,
+    keys: K::Column<'a>,
+    values: V::Column<'a>,
+}
+
+impl<'a, K: FromArrow, V: FromArrow> MapColumn<'a, K, V> {
+    fn try_new(array: &'a dyn Array) -> Result<Self, ArrowError> {
+        let map = array.as_map_opt().ok_or_else(|| {
+            ArrowError::CastError(format!("expected Map but got {}", array.data_type()))
+        })?;
+
+        Ok(Self {
+            offsets: map.value_offsets(),
+            nulls: map.nulls(),
+            keys: K::column(map.keys().as_ref())?,
+            values: V::column(map.values().as_ref())?,
+        })
+    }
+
+    /// Reads the entries of one map, or `None` if the map or any value is null.
+    fn entries<M: FromIterator<(K, V)>>(&self, index: usize) -> Option<M> {
+        if self.nulls.is_some_and(|nulls| nulls.is_null(index)) {
+            return None;
+        }
+
+        let range = self.offsets[index] as usize..self.offsets[index + 1] as usize;
+        range
+            .map(|entry| {
+                let key = K::from_column(&self.keys, entry)?;
+                let value = V::from_column(&self.values, entry)?;
+                Some((key, value))
+            })
+            .collect()
+    }
+}
+
+/// Returns a map type with Arrow's default `entries: Struct<key, value>` field.
+fn map_data_type(key_type: DataType, value_type: DataType) -> DataType {
+    let entries = Field::new(
+        "entries",
+        DataType::Struct(Fields::from(vec![
+            Field::new("key", key_type, false),
+            Field::new("value", value_type, true),
+        ])),
+        false,
+    );
+    DataType::Map(Arc::new(entries), false)
+}
+
+/// Output builder for `MAP<K,V>` values, appending entries to the builders of
+/// `K` and `V`.
+///
+/// The entries, key and value field names of the requested output type are
+/// reused, so the result matches the output schema. If that type has sorted
+/// keys, the entries of each map are sorted by key.
+pub struct MapOutputBuilder<K: ToArrow, V: ToArrow> {
+    keys: K::Builder,
+    values: V::Builder,
+    entries_field: FieldRef,
+    key_field: FieldRef,
+    value_field: FieldRef,
+    sorted: bool,
+    lengths: Vec<usize>,
+    validity: Vec<bool>,
+}
+
+impl<K: ToArrow, V: ToArrow> MapOutputBuilder<K, V> {
+    fn new(capacity: usize, data_type: Option<&DataType>) -> Result<Self, ArrowError> {
+        let requested = data_type.is_some();
+        let data_type = data_type
+            .cloned()
+            .unwrap_or_else(|| map_data_type(K::data_type(), V::data_type()));
+        let DataType::Map(entries_field, sorted) = &data_type else {
+            return Err(ArrowError::CastError(format!(
+                "Cannot write map values to a {} column",
+                data_type
+            )));
+        };
+        let DataType::Struct(entry_fields) = entries_field.data_type() else {
+            return Err(ArrowError::CastError(format!(
+                "Map entries must be a struct but got {}",
+                entries_field.data_type()
+            )));
+        };
+        let [key_field, value_field] = &entry_fields[..] else {
+            return Err(ArrowError::CastError(format!(
+                "Map entries must have a key and a value field but got {}",
+                entries_field.data_type()
+            )));
+        };
+
+        Ok(Self {
+            keys: K::builder(capacity, requested.then(|| key_field.data_type()))?,
+            values: V::builder(capacity, requested.then(|| value_field.data_type()))?,
+            entries_field: entries_field.clone(),
+            key_field: key_field.clone(),
+            value_field: value_field.clone(),
+            sorted: *sorted,
+            lengths: Vec::with_capacity(capacity),
+            validity: Vec::with_capacity(capacity),
+        })
+    }
+}
+
+impl<K, V, M> OutputBuilder<M> for MapOutputBuilder<K, V>
+where
+    K: ToArrow,
+    V: ToArrow,
+    M: IntoIterator<Item = (K, V)>,
+{
+    fn append_value(&mut self, value: M) -> Result<(), ArrowError> {
+        let mut len = 0;
+        for (key, value) in value {
+            self.keys.append_value(key)?;
+            self.values.append_value(value)?;
+            len += 1;
+        }
+        self.lengths.push(len);
+        self.validity.push(true);
+        Ok(())
+    }
+
+    fn append_null(&mut self) {
+        self.lengths.push(0);
+        self.validity.push(false);
+    }
+
+    fn finish(self) -> Result<ArrayRef, ArrowError> {
+        let mut keys = self.keys.finish()?;
+        let mut items = self.values.finish()?;
+        if self.sorted {
+            let order = sorted_entries(keys.as_ref(), &self.lengths)?;
+            keys = take(keys.as_ref(), &order, None)?;
+            items = take(items.as_ref(), &order, None)?;
+        }
+        let entry_fields = Fields::from(vec![
+            self.key_field
+                .as_ref()
+                .clone()
+                .with_data_type(keys.data_type().clone()),
+            self.value_field
+                .as_ref()
+                .clone()
+                .with_data_type(items.data_type().clone()),
+        ]);
+        let entries_field = Arc::new(
+            self.entries_field
+                .as_ref()
+                .clone()
+                .with_data_type(DataType::Struct(entry_fields.clone())),
+        );
+
+        let entries = StructArray::try_new(entry_fields, vec![keys, items], None)?;
+        let map = MapArray::try_new(
+            entries_field,
+            OffsetBuffer::from_lengths(self.lengths),
+            entries,
+            Some(NullBuffer::from(self.validity)),
+            self.sorted,
+        )?;
+        Ok(Arc::new(map))
+    }
+}
+
+/// Returns the order of the entries of maps with the given lengths that sorts
+/// the keys within each map, as a requested sorted map type promises.
+///
+/// The entries of a `HashMap` come in arbitrary order, so they are sorted
+/// after building rather than trusted to arrive sorted.
+fn sorted_entries(keys: &dyn Array, lengths: &[usize]) -> Result<UInt32Array, ArrowError> {
+    let mut order = Vec::with_capacity(keys.len());
+    let mut start = 0;
+    for &len in lengths {
+        let indices = sort_to_indices(&keys.slice(start, len), None, None)?;
+        order.extend(indices.values().iter().map(|index| start as u32 + index));
+        start += len;
+    }
+    Ok(UInt32Array::from(order))
+}
+
+/// Converts Athena `MAP<K,V>` values from Arrow `Map` arrays into a `HashMap`.
+///
+/// Keys and values are read with their own [`FromArrow`] implementations. A map
+/// with a null value is treated like a null input for `HashMap<K, V>`; use
+/// `HashMap<K, Option<V>>` to receive null values.
+///
+/// # Examples
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use arrow::array::{Int64Builder, MapBuilder, StringBuilder};
+/// # use athena_udf::arrow_conversions::FromArrow;
+/// let mut builder = MapBuilder::new(None, StringBuilder::new(), Int64Builder::new());
+/// builder.keys().append_value("a");
+/// builder.values().append_value(1);
+/// builder.append(true).unwrap();
+/// let array = builder.finish();
+///
+/// let map = HashMap::<String, i64>::from_array(&array, 0).unwrap();
+/// assert_eq!(map["a"], 1);
+/// ```
+impl<K, V> FromArrow for HashMap<K, V>
+where
+    K: FromArrow + Eq + Hash,
+    V: FromArrow,
+{
+    type Column<'a> = MapColumn<'a, K, V>;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        MapColumn::try_new(array)
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        column.entries(index)
+    }
+
+    fn array_type() -> DataType {
+        map_data_type(K::array_type(), V::array_type())
+    }
+}
+
+/// Converts Athena `MAP<K,V>` values from Arrow `Map` arrays into a `BTreeMap`.
+///
+/// Behaves like the `HashMap` conversion, but keeps the keys ordered.
+impl<K, V> FromArrow for BTreeMap<K, V>
+where
+    K: FromArrow + Ord,
+    V: FromArrow,
+{
+    type Column<'a> = MapColumn<'a, K, V>;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        MapColumn::try_new(array)
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        column.entries(index)
+    }
+
+    fn array_type() -> DataType {
+        map_data_type(K::array_type(), V::array_type())
+    }
+}
+
+/// Converts `HashMap<K, V>` values to Arrow `Map` arrays for Athena `MAP<K,V>` results.
+///
+/// The entries, key and value field names are taken from the requested output
+/// type, falling back to Arrow's `entries`, `key` and `value`.
+impl<K, V> ToArrow for HashMap<K, V>
+where
+    K: ToArrow,
+    V: ToArrow,
+{
+    type ArrayType = MapArray;
+    type Builder = MapOutputBuilder<K, V>;
+
+    fn data_type() -> DataType {
+        map_data_type(K::data_type(), V::data_type())
+    }
+
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        MapOutputBuilder::new(capacity, data_type)
+    }
+}
+
+/// Converts `BTreeMap<K, V>` values to Arrow `Map` arrays for Athena `MAP<K,V>` results.
+///
+/// Entries are written in key order; field names follow the requested output type.
+impl<K, V> ToArrow for BTreeMap<K, V>
+where
+    K: ToArrow,
+    V: ToArrow,
+{
+    type ArrayType = MapArray;
+    type Builder = MapOutputBuilder<K, V>;
+
+    fn data_type() -> DataType {
+        map_data_type(K::data_type(), V::data_type())
+    }
+
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        MapOutputBuilder::new(capacity, data_type)
+    }
+}
+
+/// Downcasts an input column to a `StructArray` for a derived `ROW` type.
+#[doc(hidden)]
+pub fn struct_column(array: &dyn Array) -> Result<&StructArray, ArrowError> {
+    array.as_struct_opt().ok_or_else(|| {
+        ArrowError::CastError(format!("expected Struct but got {}", array.data_type()))
+    })
+}
+
+/// Returns the child array of a `ROW` field by name.
+#[doc(hidden)]
+pub fn struct_child<'a>(array: &'a StructArray, name: &str) -> Result<&'a dyn Array, ArrowError> {
+    array
+        .column_by_name(name)
+        .map(|child| child.as_ref())
+        .ok_or_else(|| {
+            ArrowError::SchemaError(format!(
+                "ROW field {:?} not found in {}",
+                name,
+                array.data_type()
+            ))
+        })
+}
+
+/// Returns the requested type of a `ROW` field, or `default` when the output
+/// type is not a struct or does not name the field.
+#[doc(hidden)]
+pub fn struct_child_type(data_type: &DataType, name: &str, default: DataType) -> DataType {
+    match data_type {
+        DataType::Struct(fields) => fields
+            .find(name)
+            .map_or(default, |(_, field)| field.data_type().clone()),
+        _ => default,
+    }
+}
+
+/// Builds a `StructArray` for a derived `ROW` type from its named child arrays.
+///
+/// Children are ordered like the requested struct fields and keep their
+/// nullability and metadata. Every requested field must be provided and every
+/// child must be requested.
+#[doc(hidden)]
+pub fn build_struct(
+    data_type: &DataType,
+    children: Vec<(&str, ArrayRef)>,
+    validity: Vec<bool>,
+) -> Result<ArrayRef, ArrowError> {
+    let DataType::Struct(requested) = data_type else {
+        return Err(ArrowError::CastError(format!(
+            "Cannot write ROW values to a {} column",
+            data_type
+        )));
+    };
+    if requested.len() != children.len() {
+        return Err(ArrowError::SchemaError(format!(
+            "ROW has {} fields but {} were requested by {}",
+            children.len(),
+            requested.len(),
+            data_type
+        )));
+    }
+
+    let mut fields = Vec::with_capacity(children.len());
+    let mut arrays = Vec::with_capacity(children.len());
+    for field in requested {
+        let (_, array) = children
+            .iter()
+            .find(|(name, _)| name == field.name())
+            .ok_or_else(|| {
+                ArrowError::SchemaError(format!(
+                    "ROW has no field {:?} requested by {}",
+                    field.name(),
+                    data_type
+                ))
+            })?;
+        fields.push(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(array.data_type().clone()),
+        );
+        arrays.push(array.clone());
+    }
+
+    let array = StructArray::try_new(
+        Fields::from(fields),
+        arrays,
+        Some(NullBuffer::from(validity)),
+    )?;
+    Ok(Arc::new(array))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UDFProcessor;
+    use arrow::array::{
+        Int64Array, Int64Builder, LargeListArray, MapBuilder, MapFieldNames, StringArray,
+        StringBuilder,
+    };
+    use arrow::datatypes::{Int64Type, Schema};
+    use arrow::record_batch::RecordBatch;
+
+    #[test]
+    fn test_vec_from_list_of_strings() {
+        let values = StringArray::from(vec!["a", "b", "c"]);
+        let array = ListArray::new(
+            Arc::new(Field::new_list_field(DataType::Utf8, true)),
+            OffsetBuffer::from_lengths([2, 0, 1]),
+            Arc::new(values),
+            None,
+        );
+
+        assert_eq!(
+            Vec::<String>::from_array(&array, 0),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(Vec::<String>::from_array(&array, 1), Some(vec![]));
+        assert_eq!(
+            Vec::<String>::from_array(&array, 2),
+            Some(vec!["c".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_vec_from_large_list() {
+        let array = LargeListArray::from_iter_primitive::<Int64Type, _, _>(vec![Some(vec![
+            Some(7),
+            Some(8),
+        ])]);
+
+        assert_eq!(Vec::<i64>::from_array(&array, 0), Some(vec![7, 8]));
+    }
+
+    #[test]
+    fn test_vec_rejects_element_type_mismatch() {
+        let array = ListArray::from_iter_primitive::<Int64Type, _, _>(vec![Some(vec![Some(1)])]);
+
+        assert!(Vec::<String>::column(&array).is_err());
+    }
+
+    #[test]
+    fn test_nested_vec_round_trip() {
+        let values = vec![Some(vec![vec![1i64], vec![2, 3]]), None];
+        let array = Vec::<Vec<i64>>::to_array(values);
+
+        assert_eq!(
+            Vec::<Vec<i64>>::from_array(array.as_ref(), 0),
+            Some(vec![vec![1], vec![2, 3]])
+        );
+        assert_eq!(Vec::<Vec<i64>>::from_array(array.as_ref(), 1), None);
+    }
+
+    #[test]
+    fn test_vec_option_to_arrow_keeps_null_elements() {
+        let array = Vec::<Option<String>>::to_array(vec![Some(vec![Some("x".to_string()), None])]);
+        let list = array.as_list::<i32>();
+        let items = list.value(0);
+        let items = items.as_string::<i32>();
+
+        assert_eq!(items.value(0), "x");
+        assert!(items.is_null(1));
+    }
+
+    #[test]
+    fn test_vec_to_arrow_uses_requested_item_field() {
+        let item_field = Arc::new(Field::new("element", DataType::Int64, true));
+        let data_type = DataType::LargeList(item_field.clone());
+        let array = Vec::<i64>::to_array_as(vec![Some(vec![1, 2])], &data_type).unwrap();
+
+        assert_eq!(array.data_type(), &data_type);
+    }
+
+    #[test]
+    fn test_list_builder_skips_failed_value() {
+        let item_field = Arc::new(Field::new_list_field(DataType::Decimal128(3, 0), true));
+        let data_type = DataType::List(item_field);
+        let mut builder = Vec::<crate::Decimal>::builder(1, Some(&data_type)).unwrap();
+
+        let overflow = vec![crate::Decimal::new(1, 0), crate::Decimal::new(1000, 0)];
+        assert!(builder.append_value(overflow).is_err());
+        assert!(builder.lengths.is_empty());
+        assert!(builder.validity.is_empty());
+    }
+
+    fn string_map_array() -> MapArray {
+        let mut builder = MapBuilder::new(None, StringBuilder::new(), Int64Builder::new());
+        builder.keys().append_value("a");
+        builder.values().append_value(1);
+        builder.keys().append_value("b");
+        builder.values().append_null();
+        builder.append(true).unwrap();
+        builder.append(false).unwrap();
+        builder.keys().append_value("c");
+        builder.values().append_value(3);
+        builder.append(true).unwrap();
+        builder.finish()
+    }
+
+    #[test]
+    fn test_hash_map_from_arrow() {
+        let array = string_map_array();
+
+        assert_eq!(HashMap::<String, i64>::from_array(&array, 0), None);
+        assert_eq!(
+            HashMap::<String, Option<i64>>::from_array(&array, 0),
+            Some(HashMap::from([
+                ("a".to_string(), Some(1)),
+                ("b".to_string(), None)
+            ]))
+        );
+        assert_eq!(HashMap::<String, i64>::from_array(&array, 1), None);
+        assert_eq!(
+            BTreeMap::<String, i64>::from_array(&array, 2),
+            Some(BTreeMap::from([("c".to_string(), 3)]))
+        );
+    }
+
+    #[test]
+    fn test_map_round_trip() {
+        let values = vec![
+            Some(BTreeMap::from([
+                ("x".to_string(), 1i64),
+                ("y".to_string(), 2),
+            ])),
+            None,
+        ];
+        let array = BTreeMap::<String, i64>::to_array(values.clone());
+
+        assert_eq!(
+            BTreeMap::<String, i64>::from_array(array.as_ref(), 0),
+            values[0]
+        );
+        assert_eq!(BTreeMap::<String, i64>::from_array(array.as_ref(), 1), None);
+    }
+
+    #[test]
+    fn test_map_to_arrow_uses_requested_field_names() {
+        let mut builder = MapBuilder::new(
+            Some(MapFieldNames {
+                entry: "entries".to_string(),
+                key: "k".to_string(),
+                value: "v".to_string(),
+            }),
+            StringBuilder::new(),
+            Int64Builder::new(),
+        );
+        let data_type = builder.finish().data_type().clone();
+        let values = vec![Some(HashMap::from([("a".to_string(), 1i64)]))];
+        let array = HashMap::<String, i64>::to_array_as(values, &data_type).unwrap();
+
+        let (key_field, value_field) = array.as_map().entries_fields();
+        assert_eq!(key_field.name(), "k");
+        assert_eq!(value_field.name(), "v");
+    }
+
+    #[test]
+    fn test_map_to_arrow_sorts_entries_of_sorted_map_type() {
+        let DataType::Map(entries_field, _) = HashMap::<String, i64>::data_type() else {
+            unreachable!()
+        };
+        let data_type = DataType::Map(entries_field, true);
+        let values = vec![
+            Some(HashMap::from([
+                ("c".to_string(), 3i64),
+                ("a".to_string(), 1),
+                ("b".to_string(), 2),
+            ])),
+            None,
+            Some(HashMap::from([
+                ("z".to_string(), 26),
+                ("y".to_string(), 25),
+            ])),
+        ];
+        let array = HashMap::<String, i64>::to_array_as(values, &data_type).unwrap();
+
+        let map = array.as_map();
+        assert_eq!(map.data_type(), &data_type);
+        let keys = map.keys().as_string::<i32>();
+        let values = map.values().as_primitive::<Int64Type>();
+        assert_eq!(
+            keys.iter().flatten().collect::<Vec<_>>(),
+            ["a", "b", "c", "y", "z"]
+        );
+        assert_eq!(values.values(), &[1, 2, 3, 25, 26]);
+        assert!(map.is_null(1));
+    }
+
+    #[test]
+    fn test_process_unary_vec_to_vec() {
+        let input_array = ListArray::from_iter_primitive::<Int64Type, _, _>(vec![
+            Some(vec![Some(3), Some(1), Some(2)]),
+            None,
+        ]);
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "tags",
+            input_array.data_type().clone(),
+            true,
+        )]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor.process_unary::<Vec<i64>, Vec<i64>, _>("sorted", |mut values| {
+            values.sort();
+            values
+        });
+
+        assert!(result.is_ok());
+        let output_batch = result.unwrap();
+        let output_array = output_batch.column(0).as_list::<i32>();
+        let first = output_array.value(0);
+
+        assert_eq!(
+            first
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap()
+                .values(),
+            &[1, 2, 3]
+        );
+        assert!(output_array.is_null(1));
+    }
+
+    #[cfg(feature = "derive")]
+    mod row {
+        use super::*;
+        use crate::{FromArrow, ToArrow};
+
+        #[derive(Debug, PartialEq, FromArrow, ToArrow)]
+        struct Address {
+            street: String,
+            #[athena_udf(rename = "zip_code")]
+            zip: Option<String>,
+        }
+
+        fn address_array() -> StructArray {
+            StructArray::try_new(
+                Fields::from(vec![
+                    Field::new("zip_code", DataType::Utf8, true),
+                    Field::new("street", DataType::Utf8, true),
+                ]),
+                vec![
+                    Arc::new(StringArray::from(vec![Some("10115"), None, None])),
+                    Arc::new(StringArray::from(vec![
+                        Some("Main St"),
+                        Some("Elm St"),
+                        None,
+                    ])),
+                ],
+                Some(NullBuffer::from(vec![true, true, false])),
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn test_derived_row_from_arrow() {
+            let array = address_array();
+
+            assert_eq!(
+                Address::from_array(&array, 0),
+                Some(Address {
+                    street: "Main St".to_string(),
+                    zip: Some("10115".to_string()),
+                })
+            );
+            assert_eq!(
+                Address::from_array(&array, 1),
+                Some(Address {
+                    street: "Elm St".to_string(),
+                    zip: None,
+                })
+            );
+            assert_eq!(Address::from_array(&array, 2), None);
+        }
+
+        #[test]
+        fn test_derived_row_rejects_missing_field() {
+            let array = StructArray::try_new(
+                Fields::from(vec![Field::new("street", DataType::Utf8, true)]),
+                vec![Arc::new(StringArray::from(vec!["Main St"]))],
+                None,
+            )
+            .unwrap();
+
+            assert!(Address::column(&array).is_err());
+        }
+
+        #[test]
+        fn test_derived_row_to_arrow_follows_requested_fields() {
+            let data_type = address_array().data_type().clone();
+            let values = vec![
+                Some(Address {
+                    street: "Main St".to_string(),
+                    zip: None,
+                }),
+                None,
+            ];
+            let array = Address::to_array_as(values, &data_type).unwrap();
+            let row = array.as_struct();
+
+            assert_eq!(array.data_type(), &data_type);
+            assert_eq!(row.column(1).as_string::<i32>().value(0), "Main St");
+            assert!(row.column(0).is_null(0));
+            assert!(row.is_null(1));
+        }
+
+        #[test]
+        fn test_derived_row_to_arrow_rejects_extra_requested_fields() {
+            let data_type = DataType::Struct(Fields::from(vec![
+                Field::new("street", DataType::Utf8, true),
+                Field::new("zip_code", DataType::Utf8, true),
+                Field::new("city", DataType::Utf8, true),
+            ]));
+            let values = vec![Some(Address {
+                street: "Main St".to_string(),
+                zip: None,
+            })];
+
+            let message = Address::to_array_as(values, &data_type)
+                .unwrap_err()
+                .to_string();
+            assert!(message.contains("ROW has 2 fields but 3 were requested by Struct("));
+        }
+
+        #[test]
+        fn test_process_unary_row_to_string() {
+            let input_array = address_array();
+            let schema = Arc::new(Schema::new(vec![Field::new(
+                "address",
+                input_array.data_type().clone(),
+                true,
+            )]));
+            let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+            let processor = UDFProcessor::new(&batch);
+            let result = processor.process_unary::<Address, String, _>("label", |address| {
+                format!("{} {}", address.street, address.zip.unwrap_or_default())
+            });
+
+            let output_batch = result.unwrap();
+            let output_array = output_batch.column(0).as_string::<i32>();
+            assert_eq!(output_array.value(0), "Main St 10115");
+            assert_eq!(output_array.value(1), "Elm St ");
+            assert!(output_array.is_null(2));
+        }
+    }
+}
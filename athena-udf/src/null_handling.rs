@@ -0,0 +1,103 @@
+use arrow::array::ArrayRef;
+use arrow::buffer::NullBuffer;
+
+/// When a UDF is called for rows with null inputs, in the spirit of SQL's
+/// `RETURNS NULL ON NULL INPUT` and `CALLED ON NULL INPUT`.
+///
+/// Whether an argument accepts nulls is part of its type: an `Option<T>`
+/// parameter receives a null as `None`, while a plain `T` parameter never sees
+/// one. The null handling of a function decides how the two interact.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{Array, StringArray};
+/// # use arrow::datatypes::{DataType, Field, Schema};
+/// # use arrow::record_batch::RecordBatch;
+/// # use std::sync::Arc;
+/// # use athena_udf::{NullHandling, UDFProcessor};
+/// let schema = Arc::new(Schema::new(vec![
+///     Field::new("name", DataType::Utf8, true),
+///     Field::new("title", DataType::Utf8, true),
+/// ]));
+/// let name = StringArray::from(vec![Some("Ada"), None, Some("Alan")]);
+/// let title = StringArray::from(vec![Some("Dr."), Some("Prof."), None]);
+/// let batch = RecordBatch::try_new(schema, vec![Arc::new(name), Arc::new(title)]).unwrap();
+///
+/// let greet = |name: String, title: Option<String>| match title {
+///     Some(title) => format!("{} {}", title, name),
+///     None => name,
+/// };
+///
+/// // A null name makes the result NULL, a null title is passed as `None`
+/// let output_batch = UDFProcessor::new(&batch)
+///     .process_binary::<String, Option<String>, String, _>("greeting", greet)
+///     .unwrap();
+/// let output_array = output_batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+/// assert_eq!(output_array.value(0), "Dr. Ada");
+/// assert!(output_array.is_null(1));
+/// assert_eq!(output_array.value(2), "Alan");
+///
+/// // Any null makes the result NULL
+/// let output_batch = UDFProcessor::new(&batch)
+///     .with_null_handling(NullHandling::ReturnsNullOnNullInput)
+///     .process_binary::<String, Option<String>, String, _>("greeting", greet)
+///     .unwrap();
+/// let output_array = output_batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+/// assert!(output_array.is_null(2));
+///
+/// // Every row is passed to the function, so `name` must be an `Option` too
+/// assert!(UDFProcessor::new(&batch)
+///     .with_null_handling(NullHandling::CalledOnNullInput)
+///     .process_binary::<String, Option<String>, String, _>("greeting", greet)
+///     .is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NullHandling {
+    /// Each argument follows its type: `Option<T>` arguments receive nulls as
+    /// `None`, and a null for any other argument makes the result NULL without
+    /// calling the UDF.
+    #[default]
+    PerArgument,
+    /// A null in any argument, including `Option<T>` ones, makes the result NULL
+    /// without calling the UDF.
+    ReturnsNullOnNullInput,
+    /// The UDF is called for every row. All arguments must be declared as
+    /// `Option<T>`, which is checked before any row is processed.
+    CalledOnNullInput,
+}
+
+impl NullHandling {
+    /// Checks that the arguments of a UDF can be passed the way this mode requires.
+    ///
+    /// `nullable` holds [`FromArrowRef::NULLABLE`](crate::FromArrowRef::NULLABLE)
+    /// for every argument.
+    pub(crate) fn check_arguments(
+        self,
+        method_name: &str,
+        nullable: &[bool],
+    ) -> Result<(), String> {
+        if self != NullHandling::CalledOnNullInput {
+            return Ok(());
+        }
+        match nullable.iter().position(|nullable| !nullable) {
+            Some(arg_idx) => Err(format!(
+                "{} is called on null input, but argument {} is not an Option",
+                method_name,
+                arg_idx + 1
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the rows the UDF must not be called for because of a null input,
+    /// beyond the nulls that the argument types skip themselves.
+    pub(crate) fn null_rows(self, columns: &[ArrayRef]) -> Option<NullBuffer> {
+        if self != NullHandling::ReturnsNullOnNullInput {
+            return None;
+        }
+        columns.iter().fold(None, |nulls, column| {
+            NullBuffer::union(nulls.as_ref(), column.logical_nulls().as_ref())
+        })
+    }
+}
@@ -0,0 +1,365 @@
+use crate::coercion::Coercion;
+use crate::context::UdfContext;
+use crate::fallible::{ErrorPolicy, FailedRows};
+use crate::memoize::Memoization;
+use crate::null_handling::NullHandling;
+use crate::process_macro::UDFProcessor;
+use arrow::array::ArrayRef;
+use arrow::compute::concat_batches;
+use arrow::datatypes::{DataType, Field};
+use arrow::record_batch::RecordBatch;
+use rayon::prelude::*;
+
+/// Generates process methods that split the batch into chunks and run the
+/// matching [`UDFProcessor`] method on each chunk in parallel.
+///
+/// The generated methods have the same signature as their sequential
+/// counterparts, plus an `F: Sync` bound on the user function.
+macro_rules! impl_parallel_process {
+    ($method:ident, $($input:ident),+; $output:ident) => {
+        pub fn $method<$($input,)+ $output, F>(
+            &self,
+            output_field_name: &str,
+            user_fn: F,
+        ) -> Result<RecordBatch, lambda_runtime::Error>
+        where
+            $($input: crate::FromArrowRef,)+
+            $output: crate::UdfOutput,
+            F: for<'c> Fn($($input::Value<'c>),+) -> $output + Sync,
+        {
+            self.run(|processor| {
+                processor.$method::<$($input,)+ $output, _>(output_field_name, &user_fn)
+            })
+        }
+    };
+}
+
+/// A [`UDFProcessor`] that evaluates large batches on the rayon thread pool.
+///
+/// Created with [`UDFProcessor::parallel`]. Batches with at least `min_rows` rows
+/// are split into one chunk per rayon thread; the chunks are processed
+/// concurrently and their results concatenated in order. Smaller batches are
+/// processed on the calling thread, so they don't pay for the coordination.
+///
+/// All settings of the processor apply to every chunk, and row indices in error
+/// messages refer to the whole batch. With [`ErrorPolicy::FailListing`], the
+/// failed rows of all chunks are reported together.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{Array, Int64Array};
+/// # use arrow::datatypes::{DataType, Field, Schema};
+/// # use arrow::record_batch::RecordBatch;
+/// # use std::sync::Arc;
+/// # use athena_udf::UDFProcessor;
+/// let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+/// let array = Int64Array::from_iter_values(0..100_000);
+/// let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+///
+/// let output_batch = UDFProcessor::new(&batch)
+///     .parallel(10_000)
+///     .process_unary::<i64, String, _>("label", |n| format!("#{}", n))
+///     .unwrap();
+///
+/// assert_eq!(output_batch.num_rows(), 100_000);
+/// ```
+pub struct ParallelProcessor<'a> {
+    processor: UDFProcessor<'a>,
+    min_rows: usize,
+}
+
+impl<'a> UDFProcessor<'a> {
+    /// Processes batches with at least `min_rows` rows on multiple threads.
+    ///
+    /// Requires the `rayon` feature. The UDF must be `Sync` so that it can be
+    /// called from the rayon thread pool. See [`ParallelProcessor`].
+    pub fn parallel(self, min_rows: usize) -> ParallelProcessor<'a> {
+        ParallelProcessor {
+            processor: self,
+            min_rows,
+        }
+    }
+}
+
+impl<'a> ParallelProcessor<'a> {
+    /// See [`UDFProcessor::with_output_type`].
+    pub fn with_output_type(mut self, data_type: DataType) -> Self {
+        self.processor = self.processor.with_output_type(data_type);
+        self
+    }
+
+    /// See [`UDFProcessor::with_output_field`].
+    pub fn with_output_field(mut self, field: &Field) -> Self {
+        self.processor = self.processor.with_output_field(field);
+        self
+    }
+
+    /// See [`UDFProcessor::deterministic`].
+    pub fn deterministic(mut self) -> Self {
+        self.processor = self.processor.deterministic();
+        self
+    }
+
+    /// See [`UDFProcessor::with_coercion`].
+    pub fn with_coercion(mut self, coercion: Coercion) -> Self {
+        self.processor = self.processor.with_coercion(coercion);
+        self
+    }
+
+    /// See [`UDFProcessor::with_method_name`].
+    pub fn with_method_name(mut self, method_name: &'a str) -> Self {
+        self.processor = self.processor.with_method_name(method_name);
+        self
+    }
+
+    /// See [`UDFProcessor::with_error_policy`].
+    pub fn with_error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.processor = self.processor.with_error_policy(error_policy);
+        self
+    }
+
+    /// See [`UDFProcessor::with_memoization`].
+    pub fn with_memoization(mut self, memoization: Memoization) -> Self {
+        self.processor = self.processor.with_memoization(memoization);
+        self
+    }
+
+    /// See [`UDFProcessor::with_context`].
+    pub fn with_context(mut self, context: &'a UdfContext) -> Self {
+        self.processor = self.processor.with_context(context);
+        self
+    }
+
+    /// See [`UDFProcessor::with_null_handling`].
+    pub fn with_null_handling(mut self, null_handling: NullHandling) -> Self {
+        self.processor = self.processor.with_null_handling(null_handling);
+        self
+    }
+
+    /// Runs `process` on each chunk of the batch and concatenates the results.
+    fn run<G>(&self, process: G) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        G: Fn(UDFProcessor<'_>) -> Result<RecordBatch, lambda_runtime::Error> + Sync,
+    {
+        let batch = self.processor.batch();
+        let num_rows = batch.num_rows();
+        let threads = rayon::current_num_threads();
+        if num_rows < self.min_rows.max(2) || threads < 2 {
+            return process(self.processor.for_chunk(batch, 0));
+        }
+
+        let chunk_len = num_rows.div_ceil(threads);
+        let chunks = (0..num_rows)
+            .step_by(chunk_len)
+            .map(|offset| {
+                (
+                    offset,
+                    batch.slice(offset, chunk_len.min(num_rows - offset)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let results = chunks
+            .par_iter()
+            .map(|(offset, chunk)| process(self.processor.for_chunk(chunk, *offset)))
+            .collect::<Vec<_>>();
+
+        // Failed rows of all chunks are reported together; any other error
+        // fails the batch as it would sequentially.
+        let mut output_batches = Vec::with_capacity(results.len());
+        let mut failed_rows: Option<FailedRows> = None;
+        for result in results {
+            match result {
+                Ok(output_batch) => output_batches.push(output_batch),
+                Err(error) => match error.downcast::<FailedRows>() {
+                    Ok(failed) => match failed_rows.as_mut() {
+                        Some(failed_rows) => failed_rows.merge(*failed),
+                        None => failed_rows = Some(*failed),
+                    },
+                    Err(error) => return Err(error),
+                },
+            }
+        }
+        if let Some(failed_rows) = failed_rows {
+            return Err(failed_rows.into());
+        }
+
+        Ok(concat_batches(
+            &output_batches[0].schema(),
+            &output_batches,
+        )?)
+    }
+
+    /// See [`UDFProcessor::process_columnar`].
+    pub fn process_columnar<F, E>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        F: Fn(&[ArrayRef]) -> Result<ArrayRef, E> + Sync,
+        E: Into<lambda_runtime::Error>,
+    {
+        self.run(|processor| processor.process_columnar(output_field_name, &user_fn))
+    }
+
+    /// See [`UDFProcessor::process_variadic`].
+    pub fn process_variadic<T, O, F>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        T: crate::FromArrowRef,
+        O: crate::UdfOutput,
+        F: for<'c> Fn(&[T::Value<'c>]) -> O + Sync,
+    {
+        self.run(|processor| processor.process_variadic::<T, O, _>(output_field_name, &user_fn))
+    }
+
+    /// See [`UDFProcessor::process_unary`].
+    pub fn process_unary<I1, O, F>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        I1: crate::FromArrowRef + 'static,
+        I1::Value<'static>: 'static,
+        O: crate::UdfOutput + 'static,
+        F: for<'c> Fn(I1::Value<'c>) -> O + Sync,
+    {
+        self.run(|processor| processor.process_unary::<I1, O, _>(output_field_name, &user_fn))
+    }
+
+    /// See [`UDFProcessor::process_binary`].
+    pub fn process_binary<I1, I2, O, F>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        I1: crate::FromArrowRef + 'static,
+        I1::Value<'static>: 'static,
+        I2: crate::FromArrowRef + 'static,
+        I2::Value<'static>: 'static,
+        O: crate::UdfOutput + 'static,
+        F: for<'c> Fn(I1::Value<'c>, I2::Value<'c>) -> O + Sync,
+    {
+        self.run(|processor| processor.process_binary::<I1, I2, O, _>(output_field_name, &user_fn))
+    }
+
+    impl_parallel_process!(process_ternary, I1, I2, I3; O);
+    impl_parallel_process!(process_quaternary, I1, I2, I3, I4; O);
+    impl_parallel_process!(process_quinary, I1, I2, I3, I4, I5; O);
+    impl_parallel_process!(process_senary, I1, I2, I3, I4, I5, I6; O);
+    impl_parallel_process!(process_septenary, I1, I2, I3, I4, I5, I6, I7; O);
+    impl_parallel_process!(process_octonary, I1, I2, I3, I4, I5, I6, I7, I8; O);
+    impl_parallel_process!(process_nonary, I1, I2, I3, I4, I5, I6, I7, I8, I9; O);
+    impl_parallel_process!(process_denary, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10; O);
+    impl_parallel_process!(process_undenary, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11; O);
+    impl_parallel_process!(process_duodenary, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12; O);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Int64Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn numbers(num_rows: i64) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+        let array = Int64Array::from_iter((0..num_rows).map(|n| (n % 10 != 0).then_some(n)));
+        RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap()
+    }
+
+    #[test]
+    fn test_parallel_keeps_row_order() {
+        let batch = numbers(10_000);
+        let calls = AtomicUsize::new(0);
+
+        let output_batch = UDFProcessor::new(&batch)
+            .parallel(100)
+            .process_unary::<i64, String, _>("label", |n| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                n.to_string()
+            })
+            .unwrap();
+
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(output_array.len(), 10_000);
+        assert!(output_array.is_null(0));
+        assert_eq!(output_array.value(1), "1");
+        assert_eq!(output_array.value(9_999), "9999");
+        assert_eq!(calls.load(Ordering::Relaxed), 9_000);
+    }
+
+    #[test]
+    fn test_parallel_reports_batch_row() {
+        let batch = numbers(10_000);
+
+        let error = UDFProcessor::new(&batch)
+            .parallel(100)
+            .with_method_name("check")
+            .process_unary::<i64, Result<i64, String>, _>("n", |n| {
+                if n == 7_777 {
+                    Err("unlucky".to_string())
+                } else {
+                    Ok(n)
+                }
+            })
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "check failed at row 7777 with input (7777): unlucky"
+        );
+    }
+
+    #[test]
+    fn test_parallel_fail_listing_reports_all_chunks() {
+        let batch = numbers(10_000);
+
+        let error = UDFProcessor::new(&batch)
+            .parallel(100)
+            .with_method_name("check")
+            .with_error_policy(ErrorPolicy::FailListing(2))
+            .process_unary::<i64, Result<i64, String>, _>("n", |n| {
+                if n % 3_000 == 1 {
+                    Err("unlucky".to_string())
+                } else {
+                    Ok(n)
+                }
+            })
+            .unwrap_err();
+
+        let message = error.to_string();
+        assert!(message.starts_with("check failed for 4 rows"));
+        assert!(message.contains("row 1 with input (1)"));
+        assert!(message.contains("row 3001 with input (3001)"));
+        assert!(message.ends_with("... and 2 more"));
+    }
+
+    #[test]
+    fn test_parallel_small_batch_runs_inline() {
+        let batch = numbers(50);
+        let thread = std::thread::current().id();
+
+        let output_batch = UDFProcessor::new(&batch)
+            .parallel(100)
+            .process_unary::<i64, i64, _>("same_thread", |n| {
+                assert_eq!(std::thread::current().id(), thread);
+                n
+            })
+            .unwrap();
+
+        assert_eq!(output_batch.num_rows(), 50);
+    }
+}
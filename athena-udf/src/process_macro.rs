@@ -1,15 +1,37 @@
 #![allow(unused_assignments)]
 #![allow(nonstandard_style)]
 #![allow(non_snake_case)]
+use crate::arrow_conversions::{ArrowPrimitive, FromArrow, OutputBuilder};
+use crate::coercion::Coercion;
+use crate::context::UdfContext;
+use crate::fallible::{render_row, ErrorPolicy, RowErrors};
+use crate::memoize::{self, CachedResults, Memoization};
+use crate::null_handling::NullHandling;
+use crate::signature::{self, Parameter};
+use arrow::array::{
+    make_array, new_null_array, Array, ArrayRef, AsArray, PrimitiveArray, UInt32Array,
+};
+use arrow::buffer::NullBuffer;
+use arrow::compute::{cast, concat, take};
+use arrow::datatypes::{DataType, Field, FieldRef, Int64Type, Schema};
+use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
+use futures_util::future::FutureExt;
+use futures_util::stream::StreamExt;
+use std::any::{Any, TypeId};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, OnceLock};
 
 /// Generates process methods for UDF execution with varying numbers of input parameters.
 ///
 /// This macro creates methods that:
-/// 1. Extract input columns from a RecordBatch and downcast them to the appropriate Arrow array types
-/// 2. Iterate through rows, converting Arrow values to Rust types using `FromArrow`
+/// 1. Extract input columns from a RecordBatch as typed column views using `FromArrowRef::ref_column`,
+///    decoding dictionary-encoded columns first and coercing columns that cannot
+///    be read directly according to [`UDFProcessor::with_coercion`]
+/// 2. Iterate through rows, converting Arrow values to Rust types using `FromArrowRef`
 /// 3. Apply a user-provided function to the converted values
-/// 4. Convert results back to Arrow arrays using `ToArrow`
+/// 4. Append results to a `ToArrow` builder sized for the batch, honoring the
+///    output field set with [`UDFProcessor::with_output_field`]
 /// 5. Return a new RecordBatch with the output column
 ///
 /// # Arguments
@@ -27,67 +49,319 @@ use arrow::record_batch::RecordBatch;
 ///     user_fn: F,
 /// ) -> Result<RecordBatch, lambda_runtime::Error>
 /// where
-///     I1: FromArrow,
-///     I2: FromArrow,
+///     I1: FromArrowRef,
+///     I2: FromArrowRef,
 ///     ...
-///     O: ToArrow,
-///     F: Fn(I1, I2, ...) -> O,
+///     O: UdfOutput,
+///     F: for<'c> Fn(I1::Value<'c>, I2::Value<'c>, ...) -> O,
 /// ```
 ///
+/// `O` is either a [`ToArrow`](crate::ToArrow) value or a `Result` of one. Rows for
+/// which the function returns an error are handled according to
+/// [`UDFProcessor::with_error_policy`].
+///
+/// Every [`FromArrow`](crate::FromArrow) type is its own `Value`, so functions over
+/// owned types keep their plain signatures. `&str` and `&[u8]` inputs are borrowed
+/// from the batch for the duration of the call.
+///
 /// # Null Handling
 ///
-/// If any input value is null, the result for that row will be null.
+/// If an input value is null, the result for that row will be null, unless the
+/// parameter is an `Option<T>`, which receives the null as `None`. See
+/// [`UDFProcessor::with_null_handling`] to declare this per function.
+///
+/// # Primitive Fast Path
+///
+/// [`UDFProcessor::process_unary`] and [`UDFProcessor::process_binary`] apply
+/// functions over primitive numbers (`i8` through `i64`, `f32` and `f64`) that
+/// return a primitive number to the whole column with Arrow's `unary`/`binary`
+/// kernels instead of the row loop. The output reuses the input null buffers.
+///
+/// # Dictionary Inputs
+///
+/// Dictionary-encoded input columns are decoded to their value type, so any
+/// `FromArrowRef` type can read them. If the processor is marked
+/// [`deterministic`](UDFProcessor::deterministic), a unary function runs once per
+/// dictionary value instead and the results are expanded through the dictionary keys.
 #[macro_export]
 macro_rules! impl_process {
     ($method:ident, $($input:ident),+; $output:ident) => {
-        pub fn $method<$($input,)+ $output, F>(
+        $crate::impl_process!(@fn pub $method, $($input),+; $output);
+    };
+    (@fn $vis:vis $method:ident, $($input:ident),+; $output:ident) => {
+        $vis fn $method<$($input,)+ $output, F>(
             &self,
             output_field_name: &str,
             user_fn: F,
         ) -> Result<RecordBatch, lambda_runtime::Error>
         where
-            $($input: $crate::FromArrow,)+
-            $output: $crate::ToArrow,
-            F: Fn($($input),+) -> $output,
+            $($input: $crate::FromArrowRef,)+
+            $output: $crate::UdfOutput,
+            F: for<'c> Fn($($input::Value<'c>),+) -> $output,
         {
+            self.check_inputs(
+                output_field_name,
+                &[$($crate::signature::Parameter::of::<$input>()),+],
+                false,
+            )?;
+            self.null_handling.check_arguments(
+                self.method_name.unwrap_or(output_field_name),
+                &[$($input::NULLABLE),+],
+            )?;
+
             #[allow(unused_mut)]
+            let mut inputs = self.prepare_inputs(
+                <[&str]>::len(&[$(stringify!($input)),+]),
+                output_field_name,
+            )?;
+
             let mut col_idx = 0;
+            $(
+                if $input::ref_column(inputs.columns[col_idx].as_ref()).is_err() {
+                    inputs.columns[col_idx] = self.coercion.coerce(
+                        col_idx,
+                        &inputs.columns[col_idx],
+                        &$input::ref_array_type(),
+                    )?;
+                }
+                col_idx += 1;
+            )+
+            let null_rows = self.null_handling.null_rows(&inputs.columns);
+
+            col_idx = 0;
             $(
                 #[allow(non_snake_case)]
-                let $input = self.batch.column(col_idx)
-                    .as_any()
-                    .downcast_ref::<$input::ArrayType>()
-                    .ok_or(format!("Column {} type mismatch", col_idx))?;
+                let $input = $input::ref_column(inputs.columns[col_idx].as_ref())
+                    .map_err(|e| format!("Column {} type mismatch: {}", col_idx, e))?;
                 col_idx += 1;
             )+
 
-            let num_rows = self.batch.num_rows();
-            let mut results = Vec::with_capacity(num_rows);
+            let num_rows = inputs.num_rows;
+            let mut output = self.output_builder::<$output::Value>(num_rows)?;
+            let mut errors = $crate::fallible::RowErrors::new(
+                self.error_policy,
+                self.method_name.unwrap_or(output_field_name),
+            );
 
             for row_idx in 0..num_rows {
                 let result = match (
-                    $($input::from_array($input, row_idx),)+
+                    $($input::ref_value(&$input, row_idx),)+
                 ) {
-                    ($(Some($input),)+) => Some(user_fn($($input),+)),
+                    ($(Some($input),)+)
+                        if null_rows.as_ref().is_none_or(|nulls| nulls.is_valid(row_idx)) =>
+                    {
+                        let output = ::std::panic::catch_unwind(
+                            ::std::panic::AssertUnwindSafe(|| user_fn($($input),+)),
+                        )
+                        .map_err(|payload| {
+                            errors.panicked(
+                                || self.row_offset + inputs.batch_row(row_idx),
+                                || inputs.render_row(row_idx),
+                                payload,
+                            )
+                        })?;
+                        match $crate::UdfOutput::into_result(output) {
+                            Ok(value) => Some(value),
+                            Err(error) => {
+                                errors.record(
+                                    || self.row_offset + inputs.batch_row(row_idx),
+                                    || inputs.render_row(row_idx),
+                                    error,
+                                )?;
+                                None
+                            }
+                        }
+                    }
                     _ => None,
                 };
-                results.push(result);
+                $crate::OutputBuilder::append_option(&mut output, result)?;
             }
+            errors.finish()?;
+
+            let output_array = $crate::OutputBuilder::finish(output)?;
+            self.output_batch(output_field_name, &inputs, output_array)
+        }
+    };
+}
+
+/// Generates async process methods for UDFs returning a future.
+///
+/// The generated methods work like the ones of [`impl_process!`], but call a
+/// function returning a `Future` for each row. Up to
+/// [`UDFProcessor::with_concurrency`] rows are awaited concurrently, and the
+/// results are written in row order. The futures may not borrow from the batch,
+/// so the input types are [`FromArrow`](crate::FromArrow) types.
+///
+/// # Generated Method Signature
+///
+/// ```ignore
+/// pub async fn $method<I1, I2, ..., O, F, Fut>(
+///     &self,
+///     output_field_name: &str,
+///     user_fn: F,
+/// ) -> Result<RecordBatch, lambda_runtime::Error>
+/// where
+///     I1: FromArrow,
+///     I2: FromArrow,
+///     ...
+///     O: UdfOutput,
+///     F: Fn(I1, I2, ...) -> Fut,
+///     Fut: Future<Output = O>,
+/// ```
+macro_rules! impl_process_async {
+    ($method:ident, $($input:ident),+; $output:ident) => {
+        pub async fn $method<$($input,)+ $output, F, Fut>(
+            &self,
+            output_field_name: &str,
+            user_fn: F,
+        ) -> Result<RecordBatch, lambda_runtime::Error>
+        where
+            $($input: crate::FromArrow,)+
+            $output: crate::UdfOutput,
+            F: Fn($($input),+) -> Fut,
+            Fut: std::future::Future<Output = $output>,
+        {
+            self.check_inputs(
+                output_field_name,
+                &[$(crate::signature::Parameter::of::<$input>()),+],
+                false,
+            )?;
+            self.null_handling.check_arguments(
+                self.method_name.unwrap_or(output_field_name),
+                &[$($input::NULLABLE),+],
+            )?;
+
+            #[allow(unused_mut)]
+            let mut inputs = self.prepare_inputs(
+                <[&str]>::len(&[$(stringify!($input)),+]),
+                output_field_name,
+            )?;
+
+            let mut col_idx = 0;
+            $(
+                if $input::column(inputs.columns[col_idx].as_ref()).is_err() {
+                    inputs.columns[col_idx] = self.coercion.coerce(
+                        col_idx,
+                        &inputs.columns[col_idx],
+                        &$input::array_type(),
+                    )?;
+                }
+                col_idx += 1;
+            )+
+            let null_rows = self.null_handling.null_rows(&inputs.columns);
 
-            let output_array = $output::to_array(results);
-            let output_schema = std::sync::Arc::new(arrow::datatypes::Schema::new(vec![
-                arrow::datatypes::Field::new(output_field_name, $output::data_type(), true),
-            ]));
+            col_idx = 0;
+            $(
+                let $input = $input::column(inputs.columns[col_idx].as_ref())
+                    .map_err(|e| format!("Column {} type mismatch: {}", col_idx, e))?;
+                col_idx += 1;
+            )+
+
+            let mut outputs = futures_util::stream::iter(0..inputs.num_rows)
+                .map(|row_idx| {
+                    let values = ($($input::from_column(&$input, row_idx),)+);
+                    let called = null_rows.as_ref().is_none_or(|nulls| nulls.is_valid(row_idx));
+                    let user_fn = &user_fn;
+                    async move {
+                        match values {
+                            ($(Some($input),)+) if called => {
+                                let output = async { user_fn($($input),+).await };
+                                Some(AssertUnwindSafe(output).catch_unwind().await)
+                            }
+                            _ => None,
+                        }
+                    }
+                })
+                .buffered(self.concurrency.max(1))
+                .enumerate();
+
+            let mut output_builder = self.output_builder::<$output::Value>(inputs.num_rows)?;
+            let mut errors = crate::fallible::RowErrors::new(
+                self.error_policy,
+                self.method_name.unwrap_or(output_field_name),
+            );
+
+            while let Some((row_idx, output)) = outputs.next().await {
+                let result = match output {
+                    Some(Ok(output)) => match crate::UdfOutput::into_result(output) {
+                        Ok(value) => Some(value),
+                        Err(error) => {
+                            errors.record(
+                                || self.row_offset + inputs.batch_row(row_idx),
+                                || inputs.render_row(row_idx),
+                                error,
+                            )?;
+                            None
+                        }
+                    },
+                    Some(Err(payload)) => {
+                        return Err(errors
+                            .panicked(
+                                || self.row_offset + inputs.batch_row(row_idx),
+                                || inputs.render_row(row_idx),
+                                payload,
+                            )
+                            .into());
+                    }
+                    None => None,
+                };
+                crate::OutputBuilder::append_option(&mut output_builder, result)?;
+            }
+            errors.finish()?;
 
-            Ok(RecordBatch::try_new(output_schema, vec![output_array])?)
+            let output_array = crate::OutputBuilder::finish(output_builder)?;
+            self.output_batch(output_field_name, &inputs, output_array)
         }
     };
 }
 
+/// Evaluates `$body` with `$alias` naming the primitive number type `$ty` is,
+/// or to `None` if it is none of them.
+macro_rules! with_primitive {
+    ($ty:ty, $alias:ident => $body:expr) => {{
+        let type_id = TypeId::of::<$ty>();
+        if type_id == TypeId::of::<i8>() {
+            type $alias = i8;
+            $body
+        } else if type_id == TypeId::of::<i16>() {
+            type $alias = i16;
+            $body
+        } else if type_id == TypeId::of::<i32>() {
+            type $alias = i32;
+            $body
+        } else if type_id == TypeId::of::<i64>() {
+            type $alias = i64;
+            $body
+        } else if type_id == TypeId::of::<f32>() {
+            type $alias = f32;
+            $body
+        } else if type_id == TypeId::of::<f64>() {
+            type $alias = f64;
+            $body
+        } else {
+            None
+        }
+    }};
+}
+
+/// Passes a value on as `T`, which [`with_primitive!`] found to be its own type.
+///
+/// The check compiles away, so the kernels stay free of per-row branches.
+fn same_type<V: 'static, T: 'static>(value: V) -> T {
+    let mut value = Some(value);
+    (&mut value as &mut dyn Any)
+        .downcast_mut::<Option<T>>()
+        .and_then(Option::take)
+        .expect("value of the checked type")
+}
+
 /// Processes Arrow RecordBatches by applying user-defined functions to each row.
 ///
-/// `UdfProcessor` provides methods for processing 1-6 input columns, converting
+/// `UdfProcessor` provides methods for processing 1-12 input columns, converting
 /// Arrow data to Rust types, applying transformations, and converting back to Arrow format.
+/// Functions taking any number of arguments of one type can use
+/// [`UDFProcessor::process_variadic`], and functions that work on whole arrays can use
+/// [`UDFProcessor::process_columnar`].
 ///
 /// # Examples
 ///
@@ -114,6 +388,89 @@ macro_rules! impl_process {
 /// ```
 pub struct UDFProcessor<'a> {
     batch: &'a RecordBatch,
+    /// The output field requested by Athena. Its name is replaced by the output
+    /// field name passed to the process methods.
+    output_field: Option<FieldRef>,
+    deterministic: bool,
+    coercion: Coercion,
+    method_name: Option<&'a str>,
+    error_policy: ErrorPolicy,
+    memoization: Memoization,
+    /// The request context the UDF reads, which scopes memoized results.
+    context: Option<&'a UdfContext>,
+    null_handling: NullHandling,
+    concurrency: usize,
+    /// Index of the first row of `batch` in the batch sent by Athena, when
+    /// processing one chunk of it.
+    row_offset: usize,
+}
+
+/// The number of rows an async UDF is awaited for at once, unless set with
+/// [`UDFProcessor::with_concurrency`].
+pub const DEFAULT_CONCURRENCY: usize = 16;
+
+/// Input columns prepared for row-by-row processing.
+struct Inputs {
+    columns: Vec<ArrayRef>,
+    num_rows: usize,
+    /// Keys to expand the results with, when the UDF was evaluated once per
+    /// distinct dictionary value or input tuple.
+    keys: Option<ArrayRef>,
+    /// Results of distinct input tuples found in the memoization cache.
+    cached: Option<CachedResults>,
+    /// The first batch row of each distinct value, built from `keys` when a
+    /// failed row is first reported.
+    first_rows: OnceLock<Vec<Option<usize>>>,
+}
+
+impl Inputs {
+    /// Maps results computed per distinct value back to the rows of the batch.
+    fn expand(&self, output: ArrayRef) -> Result<ArrayRef, ArrowError> {
+        let output = match &self.cached {
+            Some(cached) => cached.merge(output)?,
+            None => output,
+        };
+        match &self.keys {
+            Some(keys) => take(output.as_ref(), keys.as_ref(), None),
+            None => Ok(output),
+        }
+    }
+
+    /// Returns the batch row of a processed row, i.e. the first row that refers
+    /// to a distinct value when the UDF was evaluated per value.
+    fn batch_row(&self, row_idx: usize) -> usize {
+        let row_idx = self
+            .cached
+            .as_ref()
+            .map_or(row_idx, |cached| cached.distinct_row(row_idx));
+        let Some(keys) = &self.keys else {
+            return row_idx;
+        };
+        let first_rows = self.first_rows.get_or_init(|| {
+            let mut first_rows = Vec::new();
+            if let Ok(keys) = cast(keys, &DataType::Int64) {
+                for (batch_row, key) in keys.as_primitive::<Int64Type>().iter().enumerate() {
+                    let Some(key) = key else { continue };
+                    let key = key as usize;
+                    if key >= first_rows.len() {
+                        first_rows.resize(key + 1, None);
+                    }
+                    first_rows[key].get_or_insert(batch_row);
+                }
+            }
+            first_rows
+        });
+        first_rows
+            .get(row_idx)
+            .copied()
+            .flatten()
+            .unwrap_or(row_idx)
+    }
+
+    /// Renders the input values of a processed row for error messages.
+    fn render_row(&self, row_idx: usize) -> String {
+        render_row(&self.columns, row_idx)
+    }
 }
 
 impl<'a> UDFProcessor<'a> {
@@ -138,146 +495,1041 @@ impl<'a> UDFProcessor<'a> {
     /// let processor = UDFProcessor::new(&batch);
     /// ```
     pub fn new(batch: &'a RecordBatch) -> Self {
-        Self { batch }
+        Self {
+            batch,
+            output_field: None,
+            deterministic: false,
+            coercion: Coercion::default(),
+            method_name: None,
+            error_policy: ErrorPolicy::default(),
+            memoization: Memoization::default(),
+            context: None,
+            null_handling: NullHandling::default(),
+            concurrency: DEFAULT_CONCURRENCY,
+            row_offset: 0,
+        }
     }
 
-    impl_process!(process_unary, I1; O);
-    impl_process!(process_binary, I1, I2; O);
-    impl_process!(process_ternary, I1, I2, I3; O);
-    impl_process!(process_quaternary, I1, I2, I3, I4; O);
-    impl_process!(process_quinary, I1, I2, I3, I4, I5; O);
-    impl_process!(process_senary, I1, I2, I3, I4, I5, I6; O);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use arrow::array::{Array, Int64Array, StringArray};
-    use arrow::datatypes::{DataType, Field, Schema};
-    use arrow::record_batch::RecordBatch;
-    use std::sync::Arc;
+    /// The batch this processor reads.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn batch(&self) -> &'a RecordBatch {
+        self.batch
+    }
 
-    #[test]
-    fn test_process_unary_string() {
-        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+    /// Creates a processor with the same settings for a chunk of the batch that
+    /// starts at `row_offset`.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn for_chunk<'b>(
+        &self,
+        chunk: &'b RecordBatch,
+        row_offset: usize,
+    ) -> UDFProcessor<'b>
+    where
+        'a: 'b,
+    {
+        UDFProcessor {
+            batch: chunk,
+            output_field: self.output_field.clone(),
+            deterministic: self.deterministic,
+            coercion: self.coercion,
+            method_name: self.method_name,
+            error_policy: self.error_policy,
+            memoization: self.memoization,
+            context: self.context,
+            null_handling: self.null_handling,
+            concurrency: self.concurrency,
+            row_offset: self.row_offset + row_offset,
+        }
+    }
 
-        let input_array = StringArray::from(vec![Some("hello"), None, Some("world")]);
-        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+    /// Sets the Arrow type of the output column requested by Athena.
+    ///
+    /// Results are written with [`ToArrow::builder`](crate::ToArrow::builder),
+    /// so types like [`Decimal`](crate::Decimal) are written with the precision and
+    /// scale declared in the `outputSchema`. Without an output type, the default
+    /// Arrow type of the result is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arrow::array::{Array, Decimal128Array, Int64Array};
+    /// # use arrow::datatypes::{DataType, Field, Schema};
+    /// # use arrow::record_batch::RecordBatch;
+    /// # use std::sync::Arc;
+    /// # use athena_udf::{Decimal, UDFProcessor};
+    /// let schema = Arc::new(Schema::new(vec![Field::new("cents", DataType::Int64, true)]));
+    /// let array = Int64Array::from(vec![1999, 500]);
+    /// let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+    ///
+    /// let output_batch = UDFProcessor::new(&batch)
+    ///     .with_output_type(DataType::Decimal128(10, 2))
+    ///     .process_unary::<i64, Decimal, _>("amount", |cents| Decimal::new(cents.into(), 2))
+    ///     .unwrap();
+    ///
+    /// let output_array = output_batch.column(0).as_any().downcast_ref::<Decimal128Array>().unwrap();
+    /// assert_eq!(output_array.data_type(), &DataType::Decimal128(10, 2));
+    /// assert_eq!(output_array.value_as_string(0), "19.99");
+    /// ```
+    pub fn with_output_type(mut self, data_type: DataType) -> Self {
+        self.output_field = Some(Arc::new(Field::new("", data_type, true)));
+        self
+    }
 
-        let processor = UDFProcessor::new(&batch);
-        let result = processor.process_unary::<String, String, _>("output", |s| s.to_uppercase());
+    /// Sets the output field requested by Athena in the `outputSchema`.
+    ///
+    /// Like [`with_output_type`](Self::with_output_type), results are written
+    /// in the type of the field. An output array of another type, such as the
+    /// result of [`process_columnar`](Self::process_columnar) or an `i64` result
+    /// for an `INTEGER` field, is cast according to
+    /// [`with_coercion`](Self::with_coercion). The output batch carries the
+    /// field's nullability and metadata, and a NULL result for a non-nullable
+    /// field is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arrow::array::{Array, Int32Array, Int64Array};
+    /// # use arrow::datatypes::{DataType, Field, Schema};
+    /// # use arrow::record_batch::RecordBatch;
+    /// # use std::sync::Arc;
+    /// # use athena_udf::{Coercion, UDFProcessor};
+    /// let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+    /// let array = Int64Array::from(vec![Some(20), None]);
+    /// let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+    /// let output_field = Field::new("doubled", DataType::Int32, true);
+    ///
+    /// let output_batch = UDFProcessor::new(&batch)
+    ///     .with_output_field(&output_field)
+    ///     .with_coercion(Coercion::Lenient)
+    ///     .process_unary::<i64, i64, _>("doubled", |n| n * 2)
+    ///     .unwrap();
+    /// let output_array = output_batch.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+    /// assert_eq!(output_array.value(0), 40);
+    ///
+    /// // A NULL result does not fit a non-nullable field
+    /// let output_field = Field::new("doubled", DataType::Int64, false);
+    /// let error = UDFProcessor::new(&batch)
+    ///     .with_output_field(&output_field)
+    ///     .process_unary::<i64, i64, _>("doubled", |n| n * 2)
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "doubled returned NULL at row 1 but the output field doubled is not nullable"
+    /// );
+    /// ```
+    pub fn with_output_field(mut self, field: &Field) -> Self {
+        self.output_field = Some(Arc::new(field.clone()));
+        self
+    }
 
-        assert!(result.is_ok());
-        let output_batch = result.unwrap();
-        assert_eq!(output_batch.num_rows(), 3);
+    /// The Arrow type of the output column requested by Athena, if any.
+    fn output_type(&self) -> Option<&DataType> {
+        self.output_field.as_ref().map(|field| field.data_type())
+    }
 
-        let output_array = output_batch
-            .column(0)
-            .as_any()
-            .downcast_ref::<StringArray>()
-            .unwrap();
+    /// Declares the UDF deterministic, i.e. free of side effects and returning the
+    /// same result for the same inputs.
+    ///
+    /// This lets a unary function over a dictionary-encoded column run once per
+    /// distinct dictionary value rather than once per row, which pays off on
+    /// low-cardinality columns. The function may then also see dictionary values
+    /// that no row refers to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arrow::array::{Array, DictionaryArray, StringArray};
+    /// # use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+    /// # use arrow::record_batch::RecordBatch;
+    /// # use std::cell::Cell;
+    /// # use std::sync::Arc;
+    /// # use athena_udf::UDFProcessor;
+    /// let array: DictionaryArray<Int32Type> = vec!["red", "blue", "red", "red"].into_iter().collect();
+    /// let schema = Arc::new(Schema::new(vec![Field::new("color", array.data_type().clone(), true)]));
+    /// let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+    ///
+    /// let calls = Cell::new(0);
+    /// let output_batch = UDFProcessor::new(&batch)
+    ///     .deterministic()
+    ///     .process_unary::<&str, String, _>("upper", |s| {
+    ///         calls.set(calls.get() + 1);
+    ///         s.to_uppercase()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let output_array = output_batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+    /// assert_eq!(output_array.value(2), "RED");
+    /// assert_eq!(calls.get(), 2);
+    /// ```
+    pub fn deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
 
-        assert_eq!(output_array.value(0), "HELLO");
-        assert!(output_array.is_null(1));
-        assert_eq!(output_array.value(2), "WORLD");
+    /// Sets how input columns are coerced to the parameter types of the UDF.
+    ///
+    /// Defaults to [`Coercion::Strict`], which only applies lossless widenings such
+    /// as `INTEGER` to `i64`. See [`Coercion`] for details.
+    pub fn with_coercion(mut self, coercion: Coercion) -> Self {
+        self.coercion = coercion;
+        self
     }
 
-    #[test]
-    fn test_process_unary_i64_to_string() {
-        let schema = Arc::new(Schema::new(vec![Field::new(
-            "input",
-            DataType::Int64,
-            true,
-        )]));
+    /// Sets the name of the UDF used in error messages.
+    ///
+    /// The registration macros set this to the registered function name. Without
+    /// it, errors refer to the UDF by its output column name. It is required by
+    /// [`Memoization::Lru`], which keeps results per method name.
+    pub fn with_method_name(mut self, method_name: &'a str) -> Self {
+        self.method_name = Some(method_name);
+        self
+    }
 
-        let input_array = Int64Array::from(vec![Some(42), None, Some(100)]);
-        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+    /// Sets what happens when a UDF returning `Result` fails for a row.
+    ///
+    /// Defaults to [`ErrorPolicy::Fail`]. Error messages name the UDF, the row index
+    /// and the row's input values; see [`UdfOutput`](crate::UdfOutput) for an example.
+    pub fn with_error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
 
-        let processor = UDFProcessor::new(&batch);
-        let result =
-            processor.process_unary::<i64, String, _>("output", |n| format!("Number: {}", n));
+    /// Sets whether results are reused for repeated input tuples.
+    ///
+    /// Defaults to [`Memoization::Off`]. Memoization applies to row-wise UDFs of
+    /// any arity, including async and variadic ones, and pays off when the input
+    /// columns repeat values heavily. The number of rows served from memoized
+    /// results is logged at debug level for every batch. See [`Memoization`] for
+    /// an example.
+    pub fn with_memoization(mut self, memoization: Memoization) -> Self {
+        self.memoization = memoization;
+        self
+    }
 
-        assert!(result.is_ok());
-        let output_batch = result.unwrap();
-        let output_array = output_batch
-            .column(0)
-            .as_any()
-            .downcast_ref::<StringArray>()
-            .unwrap();
+    /// Declares that the UDF reads the request context, e.g. through a
+    /// `&UdfContext` parameter.
+    ///
+    /// Results memoized with [`Memoization::Lru`] are then only reused for
+    /// requests of the same identity, so a UDF that depends on the caller never
+    /// serves one caller's results to another. `register_udfs!` sets the context
+    /// for functions taking `&UdfContext`.
+    pub fn with_context(mut self, context: &'a UdfContext) -> Self {
+        self.context = Some(context);
+        self
+    }
 
-        assert_eq!(output_array.value(0), "Number: 42");
-        assert!(output_array.is_null(1));
-        assert_eq!(output_array.value(2), "Number: 100");
+    /// Sets when the UDF is called for rows with null inputs.
+    ///
+    /// Defaults to [`NullHandling::PerArgument`], where `Option<T>` arguments
+    /// receive nulls and a null for any other argument makes the result NULL.
+    /// See [`NullHandling`] for the other modes.
+    pub fn with_null_handling(mut self, null_handling: NullHandling) -> Self {
+        self.null_handling = null_handling;
+        self
     }
 
-    #[test]
-    fn test_process_binary_add() {
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("a", DataType::Int64, true),
-            Field::new("b", DataType::Int64, true),
-        ]));
+    /// Sets how many rows of an async UDF are awaited concurrently.
+    ///
+    /// Defaults to [`DEFAULT_CONCURRENCY`]. Results are always written in row
+    /// order; a limit of 1 awaits the rows one after another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arrow::array::{Array, Int64Array};
+    /// # use arrow::datatypes::{DataType, Field, Schema};
+    /// # use arrow::record_batch::RecordBatch;
+    /// # use std::sync::Arc;
+    /// # use athena_udf::UDFProcessor;
+    /// async fn lookup(id: i64) -> String {
+    ///     // e.g. a request to a sidecar
+    ///     format!("user-{}", id)
+    /// }
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, true)]));
+    /// let array = Int64Array::from(vec![Some(7), None]);
+    /// let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+    ///
+    /// let output_batch = UDFProcessor::new(&batch)
+    ///     .with_concurrency(4)
+    ///     .process_unary_async::<i64, String, _, _>("user", lookup)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(output_batch.num_rows(), 2);
+    /// # });
+    /// ```
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
 
-        let a_array = Int64Array::from(vec![Some(10), Some(20), None, Some(30)]);
-        let b_array = Int64Array::from(vec![Some(5), None, Some(15), Some(25)]);
-        let batch =
-            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+    /// Creates the builder that row results are appended to, sized for
+    /// `num_rows` and honoring the output type.
+    ///
+    /// When the results cannot be written as the output type directly, such as
+    /// strings for a `BIGINT` column, they are written as their default Arrow
+    /// type and [`conform_output`](Self::conform_output) casts or rejects them.
+    fn output_builder<V: crate::ToArrow>(&self, num_rows: usize) -> Result<V::Builder, ArrowError> {
+        V::builder(num_rows, self.output_type()).or_else(|_| V::builder(num_rows, None))
+    }
 
-        let processor = UDFProcessor::new(&batch);
-        let result = processor.process_binary::<i64, i64, i64, _>("sum", |a, b| a + b);
+    /// Wraps the output array of processed rows in the output batch, expanding
+    /// results computed per dictionary value to the rows of the batch.
+    fn output_batch(
+        &self,
+        output_field_name: &str,
+        inputs: &Inputs,
+        output_array: ArrayRef,
+    ) -> Result<RecordBatch, lambda_runtime::Error> {
+        let output_array = inputs.expand(output_array)?;
+        self.conform_output(output_field_name, output_array)
+    }
 
-        assert!(result.is_ok());
-        let output_batch = result.unwrap();
-        let output_array = output_batch
-            .column(0)
-            .as_any()
-            .downcast_ref::<Int64Array>()
-            .unwrap();
+    /// Wraps an output array in the output batch, conforming it to the
+    /// requested output field.
+    fn conform_output(
+        &self,
+        output_field_name: &str,
+        output_array: ArrayRef,
+    ) -> Result<RecordBatch, lambda_runtime::Error> {
+        let method_name = self.method_name.unwrap_or(output_field_name);
+        let output_field = match &self.output_field {
+            Some(field) => field.as_ref().clone().with_name(output_field_name),
+            None => Field::new(output_field_name, output_array.data_type().clone(), true),
+        };
 
-        assert_eq!(output_array.value(0), 15);
-        assert!(output_array.is_null(1));
-        assert!(output_array.is_null(2));
-        assert_eq!(output_array.value(3), 55);
-    }
+        let output_array = if output_array.data_type() == output_field.data_type() {
+            output_array
+        } else {
+            self.coercion
+                .cast(&output_array, output_field.data_type())
+                .map_err(|reason| {
+                    format!(
+                        "{} returned {} but the output type is {}: {}",
+                        method_name,
+                        output_array.data_type(),
+                        output_field.data_type(),
+                        reason
+                    )
+                })?
+        };
 
-    #[test]
-    fn test_process_ternary_concat() {
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("a", DataType::Utf8, true),
-            Field::new("b", DataType::Utf8, true),
-            Field::new("c", DataType::Utf8, true),
-        ]));
+        if !output_field.is_nullable() {
+            if let Some(row_idx) = output_array
+                .logical_nulls()
+                .and_then(|nulls| nulls.iter().position(|valid| !valid))
+            {
+                return Err(format!(
+                    "{} returned NULL at row {} but the output field {} is not nullable",
+                    method_name,
+                    self.row_offset + row_idx,
+                    output_field_name
+                )
+                .into());
+            }
+        }
 
-        let a_array = StringArray::from(vec![Some("Hello"), None]);
-        let b_array = StringArray::from(vec![Some(" "), Some("World")]);
-        let c_array = StringArray::from(vec![Some("World"), Some("!")]);
+        let output_schema = Arc::new(Schema::new(vec![output_field]));
+        Ok(RecordBatch::try_new(output_schema, vec![output_array])?)
+    }
 
-        let batch = RecordBatch::try_new(
-            schema,
-            vec![Arc::new(a_array), Arc::new(b_array), Arc::new(c_array)],
+    /// Checks the input columns of the batch against the parameters of the UDF,
+    /// so that a call with the wrong number or types of arguments fails before
+    /// any row is processed.
+    fn check_inputs(
+        &self,
+        output_field_name: &str,
+        parameters: &[Parameter],
+        variadic: bool,
+    ) -> Result<(), String> {
+        signature::check_inputs(
+            self.method_name.unwrap_or(output_field_name),
+            parameters,
+            variadic,
+            self.batch.schema_ref(),
+            self.coercion,
         )
-        .unwrap();
+    }
 
-        let processor = UDFProcessor::new(&batch);
-        let result = processor
-            .process_ternary::<String, String, String, String, _>("result", |a, b, c| {
-                format!("{}{}{}", a, b, c)
-            });
+    /// Collects the first `count` input columns, decoding dictionary-encoded ones
+    /// and reducing them to distinct input tuples if memoization is enabled.
+    fn prepare_inputs(
+        &self,
+        count: usize,
+        output_field_name: &str,
+    ) -> Result<Inputs, lambda_runtime::Error> {
+        // Results kept across invocations are looked up by method name, and the
+        // output column name does not tell UDFs apart
+        if matches!(self.memoization, Memoization::Lru(_)) && self.method_name.is_none() {
+            return Err(format!(
+                "{} uses Memoization::Lru, which requires a method name set with with_method_name",
+                output_field_name
+            )
+            .into());
+        }
+        let mut inputs = self.decode_inputs(count)?;
 
-        assert!(result.is_ok());
-        let output_batch = result.unwrap();
-        let output_array = output_batch
-            .column(0)
-            .as_any()
-            .downcast_ref::<StringArray>()
-            .unwrap();
+        if let Some(distinct) = memoize::distinct(
+            &inputs.columns,
+            self.memoization,
+            self.method_name.unwrap_or(output_field_name),
+            self.output_type(),
+            self.error_policy,
+            self.context.map(|context| &context.identity),
+        )? {
+            inputs.keys = Some(match inputs.keys {
+                Some(keys) => take(distinct.keys.as_ref(), keys.as_ref(), None)?,
+                None => distinct.keys,
+            });
+            inputs.columns = distinct.columns;
+            inputs.num_rows = distinct.num_rows;
+            inputs.cached = distinct.cached;
+        }
 
-        assert_eq!(output_array.value(0), "Hello World");
-        assert!(output_array.is_null(1));
+        Ok(inputs)
     }
 
-    #[test]
-    fn test_option_string_with_nulls() {
-        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+    /// Collects the first `count` input columns, decoding dictionary-encoded ones.
+    fn decode_inputs(&self, count: usize) -> Result<Inputs, lambda_runtime::Error> {
+        let batch = self.batch;
+
+        if self.deterministic && count == 1 {
+            if let Some(dictionary) = batch.column(0).as_any_dictionary_opt() {
+                if dictionary.values().len() < dictionary.len() {
+                    let (values, keys) = match dictionary.keys().nulls() {
+                        // Null keys get a null value of their own, so that the UDF
+                        // sees them like any other null input
+                        Some(nulls) if nulls.null_count() > 0 => {
+                            let values = dictionary.values();
+                            let null_value = values.len();
+                            let keys = dictionary
+                                .normalized_keys()
+                                .into_iter()
+                                .zip(nulls.iter())
+                                .map(
+                                    |(key, valid)| {
+                                        if valid {
+                                            key as u32
+                                        } else {
+                                            null_value as u32
+                                        }
+                                    },
+                                )
+                                .collect::<UInt32Array>();
+                            let values =
+                                concat(&[values.as_ref(), &new_null_array(values.data_type(), 1)])?;
+                            (values, Arc::new(keys) as ArrayRef)
+                        }
+                        _ => (
+                            dictionary.values().clone(),
+                            make_array(dictionary.keys().to_data()),
+                        ),
+                    };
+                    return Ok(Inputs {
+                        num_rows: values.len(),
+                        columns: vec![values],
+                        keys: Some(keys),
+                        cached: None,
+                        first_rows: OnceLock::new(),
+                    });
+                }
+            }
+        }
+
+        let columns = (0..count)
+            .map(|col_idx| {
+                let column = batch.column(col_idx);
+                match column.as_any_dictionary_opt() {
+                    Some(dictionary) => take(dictionary.values().as_ref(), dictionary.keys(), None)
+                        .map_err(|e| {
+                            format!("Column {} dictionary decoding failed: {}", col_idx, e)
+                        }),
+                    None => Ok(column.clone()),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Inputs {
+            columns,
+            num_rows: batch.num_rows(),
+            keys: None,
+            cached: None,
+            first_rows: OnceLock::new(),
+        })
+    }
+
+    /// Runs a columnar UDF that maps whole input arrays to an output array.
+    ///
+    /// The function receives the input columns of the batch as they arrive and
+    /// returns one array with a value for every row, so it can use vectorized
+    /// `arrow::compute` kernels instead of the row-by-row loop. The processor names
+    /// the output column, checks that the result has one value per input row and
+    /// conforms it to the requested output field.
+    ///
+    /// The input columns are not checked against a signature, since the function
+    /// takes untyped arrays, nor are dictionary-encoded columns decoded. The
+    /// function must check the number and types of its columns itself and
+    /// return an error for those it cannot handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `user_fn` fails, if its result has the wrong length, or
+    /// if it cannot be conformed to the output field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arrow::array::{Array, ArrayRef, Int64Array};
+    /// # use arrow::compute::kernels::numeric::add;
+    /// # use arrow::datatypes::{DataType, Field, Schema};
+    /// # use arrow::error::ArrowError;
+    /// # use arrow::record_batch::RecordBatch;
+    /// # use std::sync::Arc;
+    /// # use athena_udf::UDFProcessor;
+    /// fn add_columns(columns: &[ArrayRef]) -> Result<ArrayRef, ArrowError> {
+    ///     add(&columns[0], &columns[1])
+    /// }
+    ///
+    /// let schema = Arc::new(Schema::new(vec![
+    ///     Field::new("a", DataType::Int64, true),
+    ///     Field::new("b", DataType::Int64, true),
+    /// ]));
+    /// let a = Int64Array::from(vec![Some(1), None]);
+    /// let b = Int64Array::from(vec![Some(2), Some(3)]);
+    /// let batch = RecordBatch::try_new(schema, vec![Arc::new(a), Arc::new(b)]).unwrap();
+    ///
+    /// let output_batch = UDFProcessor::new(&batch)
+    ///     .process_columnar("sum", add_columns)
+    ///     .unwrap();
+    ///
+    /// let output_array = output_batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+    /// assert_eq!(output_array.value(0), 3);
+    /// assert!(output_array.is_null(1));
+    /// ```
+    pub fn process_columnar<F, E>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        F: Fn(&[ArrayRef]) -> Result<ArrayRef, E>,
+        E: Into<lambda_runtime::Error>,
+    {
+        let method_name = self.method_name.unwrap_or(output_field_name);
+        let output_array = catch_unwind(AssertUnwindSafe(|| user_fn(self.batch.columns())))
+            .map_err(|payload| {
+                format!(
+                    "{} panicked: {}",
+                    method_name,
+                    crate::fallible::panic_message(payload.as_ref())
+                )
+            })?
+            .map_err(Into::into)?;
+
+        if output_array.len() != self.batch.num_rows() {
+            return Err(format!(
+                "{} returned {} rows for a batch of {} rows",
+                method_name,
+                output_array.len(),
+                self.batch.num_rows()
+            )
+            .into());
+        }
+
+        self.conform_output(output_field_name, output_array)
+    }
+
+    /// Runs a variadic UDF whose arguments all have the same type.
+    ///
+    /// Every input column of the batch is read as `T`, and the function receives
+    /// one row's values as a slice, so it accepts any number of arguments. Like
+    /// the fixed-arity methods, a null in any column produces a null result
+    /// unless `T` is an `Option`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arrow::array::{Array, Int32Array, Int64Array};
+    /// # use arrow::datatypes::{DataType, Field, Schema};
+    /// # use arrow::record_batch::RecordBatch;
+    /// # use std::sync::Arc;
+    /// # use athena_udf::UDFProcessor;
+    /// fn coalesce_all(values: &[Option<i64>]) -> Option<i64> {
+    ///     values.iter().copied().flatten().next()
+    /// }
+    ///
+    /// let schema = Arc::new(Schema::new(vec![
+    ///     Field::new("a", DataType::Int64, true),
+    ///     Field::new("b", DataType::Int32, true),
+    ///     Field::new("c", DataType::Int64, true),
+    /// ]));
+    /// let a = Int64Array::from(vec![None, None]);
+    /// let b = Int32Array::from(vec![Some(2), None]);
+    /// let c = Int64Array::from(vec![Some(3), None]);
+    /// let batch =
+    ///     RecordBatch::try_new(schema, vec![Arc::new(a), Arc::new(b), Arc::new(c)]).unwrap();
+    ///
+    /// let output_batch = UDFProcessor::new(&batch)
+    ///     .process_variadic::<Option<i64>, Option<i64>, _>("first", coalesce_all)
+    ///     .unwrap();
+    ///
+    /// let output_array = output_batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+    /// assert_eq!(output_array.value(0), 2);
+    /// assert!(output_array.is_null(1));
+    /// ```
+    pub fn process_variadic<T, O, F>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        T: crate::FromArrowRef,
+        O: crate::UdfOutput,
+        F: for<'c> Fn(&[T::Value<'c>]) -> O,
+    {
+        self.check_inputs(output_field_name, &[Parameter::of::<T>()], true)?;
+        self.null_handling.check_arguments(
+            self.method_name.unwrap_or(output_field_name),
+            &vec![T::NULLABLE; self.batch.num_columns()],
+        )?;
+
+        let mut inputs = self.prepare_inputs(self.batch.num_columns(), output_field_name)?;
+        for col_idx in 0..inputs.columns.len() {
+            if T::ref_column(inputs.columns[col_idx].as_ref()).is_err() {
+                inputs.columns[col_idx] = self.coercion.coerce(
+                    col_idx,
+                    &inputs.columns[col_idx],
+                    &T::ref_array_type(),
+                )?;
+            }
+        }
+        let null_rows = self.null_handling.null_rows(&inputs.columns);
+
+        let columns = inputs
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(col_idx, column)| {
+                T::ref_column(column.as_ref())
+                    .map_err(|e| format!("Column {} type mismatch: {}", col_idx, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut values = Vec::with_capacity(columns.len());
+        let mut output = self.output_builder::<O::Value>(inputs.num_rows)?;
+        let mut errors = crate::fallible::RowErrors::new(
+            self.error_policy,
+            self.method_name.unwrap_or(output_field_name),
+        );
+
+        for row_idx in 0..inputs.num_rows {
+            values.clear();
+            values.extend(
+                columns
+                    .iter()
+                    .map_while(|column| T::ref_value(column, row_idx)),
+            );
+            let called = null_rows
+                .as_ref()
+                .is_none_or(|nulls| nulls.is_valid(row_idx));
+            let result = if values.len() == columns.len() && called {
+                let output =
+                    catch_unwind(AssertUnwindSafe(|| user_fn(&values))).map_err(|payload| {
+                        errors.panicked(
+                            || self.row_offset + inputs.batch_row(row_idx),
+                            || inputs.render_row(row_idx),
+                            payload,
+                        )
+                    })?;
+                match crate::UdfOutput::into_result(output) {
+                    Ok(value) => Some(value),
+                    Err(error) => {
+                        errors.record(
+                            || self.row_offset + inputs.batch_row(row_idx),
+                            || inputs.render_row(row_idx),
+                            error,
+                        )?;
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            output.append_option(result)?;
+        }
+        errors.finish()?;
+
+        let output_array = output.finish()?;
+        self.output_batch(output_field_name, &inputs, output_array)
+    }
+
+    /// Processes a unary UDF, see [`impl_process!`].
+    ///
+    /// Functions from one primitive number to another run with Arrow's `unary`
+    /// kernel: the output reuses the input null buffer and no row is converted
+    /// on its own. The kernel also calls the function for the values under
+    /// nulls, so if it panics, the valid rows are called again one by one to
+    /// find the row that panicked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use arrow::array::{Array, Float64Array, Int32Array};
+    /// # use arrow::datatypes::{DataType, Field, Schema};
+    /// # use arrow::record_batch::RecordBatch;
+    /// # use std::sync::Arc;
+    /// # use athena_udf::UDFProcessor;
+    /// let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, true)]));
+    /// let array = Int32Array::from(vec![Some(4), None]);
+    /// let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+    ///
+    /// let output_batch = UDFProcessor::new(&batch)
+    ///     .process_unary::<i32, f64, _>("half", |n| f64::from(n) / 2.0)
+    ///     .unwrap();
+    ///
+    /// let output_array = output_batch.column(0).as_any().downcast_ref::<Float64Array>().unwrap();
+    /// assert_eq!(output_array.value(0), 2.0);
+    /// assert!(output_array.is_null(1));
+    /// ```
+    pub fn process_unary<I1, O, F>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        I1: crate::FromArrowRef + 'static,
+        I1::Value<'static>: 'static,
+        O: crate::UdfOutput + 'static,
+        F: for<'c> Fn(I1::Value<'c>) -> O,
+    {
+        let kernel = with_primitive!(I1, P1 => with_primitive!(O, Q => {
+            Some(self.unary_kernel::<P1, Q, _>(output_field_name, |a1| {
+                same_type::<O, Q>(user_fn(same_type::<P1, I1::Value<'static>>(a1)))
+            }))
+        }));
+        match kernel {
+            Some(result) => result,
+            None => self.process_unary_rows::<I1, O, _>(output_field_name, user_fn),
+        }
+    }
+
+    /// Processes a binary UDF, see [`impl_process!`].
+    ///
+    /// Functions over two primitive numbers that return one run with Arrow's
+    /// `binary` kernel, like in [`process_unary`](Self::process_unary). The
+    /// output null buffer is the union of the input ones.
+    pub fn process_binary<I1, I2, O, F>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        I1: crate::FromArrowRef + 'static,
+        I1::Value<'static>: 'static,
+        I2: crate::FromArrowRef + 'static,
+        I2::Value<'static>: 'static,
+        O: crate::UdfOutput + 'static,
+        F: for<'c> Fn(I1::Value<'c>, I2::Value<'c>) -> O,
+    {
+        let kernel = with_primitive!(I1, P1 => with_primitive!(I2, P2 => with_primitive!(O, Q => {
+            Some(self.binary_kernel::<P1, P2, Q, _>(output_field_name, |a1, a2| {
+                same_type::<O, Q>(user_fn(
+                    same_type::<P1, I1::Value<'static>>(a1),
+                    same_type::<P2, I2::Value<'static>>(a2),
+                ))
+            }))
+        })));
+        match kernel {
+            Some(result) => result,
+            None => self.process_binary_rows::<I1, I2, O, _>(output_field_name, user_fn),
+        }
+    }
+
+    /// Runs a unary UDF from one primitive number to another with Arrow's
+    /// `unary` kernel.
+    fn unary_kernel<P1, Q, F>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        P1: ArrowPrimitive,
+        Q: ArrowPrimitive,
+        F: Fn(P1) -> Q,
+    {
+        self.check_inputs(output_field_name, &[Parameter::of::<P1>()], false)?;
+        self.null_handling.check_arguments(
+            self.method_name.unwrap_or(output_field_name),
+            &[<P1 as FromArrow>::NULLABLE],
+        )?;
+
+        let mut inputs = self.prepare_inputs(1, output_field_name)?;
+        if P1::column(inputs.columns[0].as_ref()).is_err() {
+            inputs.columns[0] = self
+                .coercion
+                .coerce(0, &inputs.columns[0], &P1::array_type())?;
+        }
+        let input = P1::column(inputs.columns[0].as_ref())
+            .map_err(|e| format!("Column 0 type mismatch: {}", e))?;
+
+        let output_array = match catch_unwind(AssertUnwindSafe(|| {
+            input.unary::<_, Q::ArrowType>(&user_fn)
+        })) {
+            Ok(output_array) => output_array,
+            Err(_) => self.kernel_rows(output_field_name, &inputs, input.nulls(), |row_idx| {
+                user_fn(input.value(row_idx))
+            })?,
+        };
+
+        self.output_batch(output_field_name, &inputs, Arc::new(output_array))
+    }
+
+    /// Runs a binary UDF over two primitive numbers with Arrow's `binary` kernel.
+    fn binary_kernel<P1, P2, Q, F>(
+        &self,
+        output_field_name: &str,
+        user_fn: F,
+    ) -> Result<RecordBatch, lambda_runtime::Error>
+    where
+        P1: ArrowPrimitive,
+        P2: ArrowPrimitive,
+        Q: ArrowPrimitive,
+        F: Fn(P1, P2) -> Q,
+    {
+        self.check_inputs(
+            output_field_name,
+            &[Parameter::of::<P1>(), Parameter::of::<P2>()],
+            false,
+        )?;
+        self.null_handling.check_arguments(
+            self.method_name.unwrap_or(output_field_name),
+            &[<P1 as FromArrow>::NULLABLE, <P2 as FromArrow>::NULLABLE],
+        )?;
+
+        let mut inputs = self.prepare_inputs(2, output_field_name)?;
+        if P1::column(inputs.columns[0].as_ref()).is_err() {
+            inputs.columns[0] = self
+                .coercion
+                .coerce(0, &inputs.columns[0], &P1::array_type())?;
+        }
+        if P2::column(inputs.columns[1].as_ref()).is_err() {
+            inputs.columns[1] = self
+                .coercion
+                .coerce(1, &inputs.columns[1], &P2::array_type())?;
+        }
+        let left = P1::column(inputs.columns[0].as_ref())
+            .map_err(|e| format!("Column 0 type mismatch: {}", e))?;
+        let right = P2::column(inputs.columns[1].as_ref())
+            .map_err(|e| format!("Column 1 type mismatch: {}", e))?;
+
+        let output_array = match catch_unwind(AssertUnwindSafe(|| {
+            arrow::compute::binary::<_, _, _, Q::ArrowType>(left, right, &user_fn)
+        })) {
+            Ok(output_array) => output_array?,
+            Err(_) => {
+                let nulls = NullBuffer::union(left.nulls(), right.nulls());
+                self.kernel_rows(output_field_name, &inputs, nulls.as_ref(), |row_idx| {
+                    user_fn(left.value(row_idx), right.value(row_idx))
+                })?
+            }
+        };
+
+        self.output_batch(output_field_name, &inputs, Arc::new(output_array))
+    }
+
+    /// Computes the output of a kernel that panicked by calling the UDF for every
+    /// valid row of `nulls` on its own.
+    ///
+    /// Fails the batch with the first row that panics. If none does, the panic
+    /// came from a value under a null, and the output is complete.
+    fn kernel_rows<Q: ArrowPrimitive>(
+        &self,
+        output_field_name: &str,
+        inputs: &Inputs,
+        nulls: Option<&NullBuffer>,
+        row_fn: impl Fn(usize) -> Q,
+    ) -> Result<PrimitiveArray<Q::ArrowType>, String> {
+        let mut values = vec![Q::default(); inputs.num_rows];
+        let mut call = |row_idx: usize| {
+            values[row_idx] =
+                catch_unwind(AssertUnwindSafe(|| row_fn(row_idx))).map_err(|payload| {
+                    RowErrors::new(
+                        self.error_policy,
+                        self.method_name.unwrap_or(output_field_name),
+                    )
+                    .panicked(
+                        || self.row_offset + inputs.batch_row(row_idx),
+                        || inputs.render_row(row_idx),
+                        payload,
+                    )
+                })?;
+            Ok::<_, String>(())
+        };
+        match nulls {
+            Some(nulls) => nulls.valid_indices().try_for_each(&mut call)?,
+            None => (0..inputs.num_rows).try_for_each(&mut call)?,
+        }
+        Ok(PrimitiveArray::new(values.into(), nulls.cloned()))
+    }
+
+    impl_process!(@fn process_unary_rows, I1; O);
+    impl_process!(@fn process_binary_rows, I1, I2; O);
+    impl_process!(process_ternary, I1, I2, I3; O);
+    impl_process!(process_quaternary, I1, I2, I3, I4; O);
+    impl_process!(process_quinary, I1, I2, I3, I4, I5; O);
+    impl_process!(process_senary, I1, I2, I3, I4, I5, I6; O);
+    impl_process!(process_septenary, I1, I2, I3, I4, I5, I6, I7; O);
+    impl_process!(process_octonary, I1, I2, I3, I4, I5, I6, I7, I8; O);
+    impl_process!(process_nonary, I1, I2, I3, I4, I5, I6, I7, I8, I9; O);
+    impl_process!(process_denary, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10; O);
+    impl_process!(process_undenary, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11; O);
+    impl_process!(process_duodenary, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12; O);
+
+    impl_process_async!(process_unary_async, I1; O);
+    impl_process_async!(process_binary_async, I1, I2; O);
+    impl_process_async!(process_ternary_async, I1, I2, I3; O);
+    impl_process_async!(process_quaternary_async, I1, I2, I3, I4; O);
+    impl_process_async!(process_quinary_async, I1, I2, I3, I4, I5; O);
+    impl_process_async!(process_senary_async, I1, I2, I3, I4, I5, I6; O);
+    impl_process_async!(process_septenary_async, I1, I2, I3, I4, I5, I6, I7; O);
+    impl_process_async!(process_octonary_async, I1, I2, I3, I4, I5, I6, I7, I8; O);
+    impl_process_async!(process_nonary_async, I1, I2, I3, I4, I5, I6, I7, I8, I9; O);
+    impl_process_async!(process_denary_async, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10; O);
+    impl_process_async!(process_undenary_async, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11; O);
+    impl_process_async!(process_duodenary_async, I1, I2, I3, I4, I5, I6, I7, I8, I9, I10, I11, I12; O);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Decimal;
+    use arrow::array::{Array, Decimal128Array, Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+    use arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_process_unary_string() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+
+        let input_array = StringArray::from(vec![Some("hello"), None, Some("world")]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor.process_unary::<String, String, _>("output", |s| s.to_uppercase());
+
+        assert!(result.is_ok());
+        let output_batch = result.unwrap();
+        assert_eq!(output_batch.num_rows(), 3);
+
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "HELLO");
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(2), "WORLD");
+    }
+
+    #[test]
+    fn test_process_unary_i64_to_string() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "input",
+            DataType::Int64,
+            true,
+        )]));
+
+        let input_array = Int64Array::from(vec![Some(42), None, Some(100)]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result =
+            processor.process_unary::<i64, String, _>("output", |n| format!("Number: {}", n));
+
+        assert!(result.is_ok());
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "Number: 42");
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(2), "Number: 100");
+    }
+
+    #[test]
+    fn test_process_binary_add() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+
+        let a_array = Int64Array::from(vec![Some(10), Some(20), None, Some(30)]);
+        let b_array = Int64Array::from(vec![Some(5), None, Some(15), Some(25)]);
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor.process_binary::<i64, i64, i64, _>("sum", |a, b| a + b);
+
+        assert!(result.is_ok());
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 15);
+        assert!(output_array.is_null(1));
+        assert!(output_array.is_null(2));
+        assert_eq!(output_array.value(3), 55);
+    }
+
+    #[test]
+    fn test_process_ternary_concat() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Utf8, true),
+        ]));
+
+        let a_array = StringArray::from(vec![Some("Hello"), None]);
+        let b_array = StringArray::from(vec![Some(" "), Some("World")]);
+        let c_array = StringArray::from(vec![Some("World"), Some("!")]);
+
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(a_array), Arc::new(b_array), Arc::new(c_array)],
+        )
+        .unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor
+            .process_ternary::<String, String, String, String, _>("result", |a, b, c| {
+                format!("{}{}{}", a, b, c)
+            });
+
+        assert!(result.is_ok());
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "Hello World");
+        assert!(output_array.is_null(1));
+    }
+
+    #[test]
+    fn test_option_string_with_nulls() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
         let input_array = StringArray::from(vec![Some("hello"), None, Some("world")]);
         let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
 
@@ -301,6 +1553,46 @@ mod tests {
         assert_eq!(output_array.value(2), "WORLD");
     }
 
+    #[test]
+    fn test_process_unary_decimal_output_type() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("12.5"), None, Some("0.125")]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch).with_output_type(DataType::Decimal128(10, 3));
+        let result =
+            processor.process_unary::<String, Decimal, _>("output", |s| s.parse().unwrap());
+
+        assert!(result.is_ok());
+        let output_batch = result.unwrap();
+        assert_eq!(
+            output_batch.schema().field(0).data_type(),
+            &DataType::Decimal128(10, 3)
+        );
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 12500);
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(2), 125);
+    }
+
+    #[test]
+    fn test_process_unary_decimal_overflow_is_error() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("12.345")]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch).with_output_type(DataType::Decimal128(10, 2));
+        let result =
+            processor.process_unary::<String, Decimal, _>("output", |s| s.parse().unwrap());
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_string_to_optional_i64() {
         let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
@@ -323,4 +1615,888 @@ mod tests {
         assert!(output_array.is_null(1)); // "invalid" -> None -> null
         assert_eq!(output_array.value(2), 100);
     }
+
+    #[test]
+    fn test_process_unary_borrowed_str() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("hello"), None, Some("héllo")]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result =
+            processor.process_unary::<&str, i64, _>("length", |s| s.chars().count() as i64);
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 5);
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(2), 5);
+    }
+
+    #[test]
+    fn test_process_binary_borrowed_and_owned() {
+        fn starts_with(s: &str, prefix: Option<&str>) -> bool {
+            s.starts_with(prefix.unwrap_or_default())
+        }
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("s", DataType::Utf8, true),
+            Field::new("prefix", DataType::Utf8, true),
+        ]));
+        let s_array = StringArray::from(vec![Some("hello"), Some("world")]);
+        let prefix_array = StringArray::from(vec![Some("he"), None]);
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(s_array), Arc::new(prefix_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result =
+            processor.process_binary::<&str, Option<&str>, bool, _>("starts_with", starts_with);
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<arrow::array::BooleanArray>()
+            .unwrap();
+
+        assert!(output_array.value(0));
+        assert!(output_array.value(1));
+    }
+
+    #[test]
+    fn test_process_unary_large_string_to_view_output() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "input",
+            DataType::LargeUtf8,
+            true,
+        )]));
+        let input_array = arrow::array::LargeStringArray::from(vec![Some("hello"), None]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch).with_output_type(DataType::Utf8View);
+        let result = processor.process_unary::<String, String, _>("output", |s| s.to_uppercase());
+
+        let output_batch = result.unwrap();
+        assert_eq!(
+            output_batch.schema().field(0).data_type(),
+            &DataType::Utf8View
+        );
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<arrow::array::StringViewArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "HELLO");
+        assert!(output_array.is_null(1));
+    }
+
+    fn dictionary_batch() -> RecordBatch {
+        let array: arrow::array::DictionaryArray<arrow::datatypes::Int8Type> =
+            vec![Some("a"), None, Some("b"), Some("a"), Some("a")]
+                .into_iter()
+                .collect();
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "input",
+            array.data_type().clone(),
+            true,
+        )]));
+        RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap()
+    }
+
+    #[test]
+    fn test_process_unary_dictionary_input() {
+        let batch = dictionary_batch();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor.process_unary::<String, String, _>("output", |s| s.to_uppercase());
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.len(), 5);
+        assert_eq!(output_array.value(0), "A");
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(2), "B");
+        assert_eq!(output_array.value(4), "A");
+    }
+
+    #[test]
+    fn test_process_deterministic_dictionary_runs_per_value() {
+        let batch = dictionary_batch();
+        let calls = std::cell::Cell::new(0);
+
+        let processor = UDFProcessor::new(&batch).deterministic();
+        let result = processor.process_unary::<&str, i64, _>("output", |s| {
+            calls.set(calls.get() + 1);
+            s.len() as i64
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(calls.get(), 2);
+        assert_eq!(output_array.len(), 5);
+        assert_eq!(output_array.value(0), 1);
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(3), 1);
+    }
+
+    #[test]
+    fn test_process_deterministic_dictionary_calls_on_null_key() {
+        let batch = dictionary_batch();
+        let calls = std::cell::Cell::new(0);
+
+        let output_batch = UDFProcessor::new(&batch)
+            .deterministic()
+            .with_null_handling(NullHandling::CalledOnNullInput)
+            .process_unary::<Option<&str>, String, _>("output", |s| {
+                calls.set(calls.get() + 1);
+                s.unwrap_or("-").to_uppercase()
+            })
+            .unwrap();
+
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(calls.get(), 3);
+        assert_eq!(output_array.value(0), "A");
+        assert_eq!(output_array.value(1), "-");
+        assert_eq!(output_array.value(2), "B");
+        assert_eq!(output_array.value(4), "A");
+    }
+
+    #[test]
+    fn test_process_binary_dictionary_and_plain_inputs() {
+        let dictionary: arrow::array::DictionaryArray<arrow::datatypes::Int32Type> =
+            vec!["x", "y", "x"].into_iter().collect();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", dictionary.data_type().clone(), true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let numbers = Int64Array::from(vec![1, 2, 3]);
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(dictionary), Arc::new(numbers)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch).deterministic();
+        let result = processor
+            .process_binary::<String, i64, String, _>("output", |s, n| format!("{}{}", s, n));
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "x1");
+        assert_eq!(output_array.value(1), "y2");
+        assert_eq!(output_array.value(2), "x3");
+    }
+
+    #[test]
+    fn test_process_binary_widens_integer_columns() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a_array = arrow::array::Int32Array::from(vec![Some(1), None]);
+        let b_array = Int64Array::from(vec![Some(2), Some(3)]);
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor.process_binary::<i64, i64, i64, _>("sum", |a, b| a + b);
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 3);
+        assert!(output_array.is_null(1));
+    }
+
+    #[test]
+    fn test_process_unary_kernel_reuses_null_buffer() {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int32, true)]));
+        let input_array = arrow::array::Int32Array::from(vec![Some(4), None, Some(-2)]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor.process_unary::<i32, f64, _>("inverse", |n| 1.0 / f64::from(n));
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 0.25);
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(2), -0.5);
+        assert_eq!(
+            output_array.nulls().unwrap().buffer().as_ptr(),
+            batch.column(0).nulls().unwrap().buffer().as_ptr()
+        );
+    }
+
+    #[test]
+    fn test_process_binary_kernel_skips_null_rows() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int16, true),
+        ]));
+        let a_array = Int64Array::from(vec![Some(10), None, Some(9), Some(7)]);
+        let b_array = arrow::array::Int16Array::from(vec![Some(3), Some(0), None, Some(7)]);
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        // The kernel panics on the 0 under the null in row 1, so the valid rows
+        // are computed one by one
+        let processor = UDFProcessor::new(&batch);
+        let result =
+            processor.process_binary::<i64, i16, i64, _>("quotient", |a, b| a / i64::from(b));
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 3);
+        assert!(output_array.is_null(1));
+        assert!(output_array.is_null(2));
+        assert_eq!(output_array.value(3), 1);
+    }
+
+    #[test]
+    fn test_process_strict_coercion_rejects_narrowing() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Float64, true),
+        ]));
+        let a_array = StringArray::from(vec!["x"]);
+        let b_array = arrow::array::Float64Array::from(vec![1.5]);
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor.process_binary::<String, i64, String, _>("output", |s, _| s);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "output expects (VARCHAR, BIGINT) but got (VARCHAR, DOUBLE)"
+        );
+    }
+
+    #[test]
+    fn test_process_lenient_coercion() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("42"), None]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch).with_coercion(Coercion::Lenient);
+        let result = processor.process_unary::<i64, i64, _>("output", |n| n * 2);
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 84);
+        assert!(output_array.is_null(1));
+    }
+
+    #[test]
+    fn test_process_fallible_lists_errors() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("1"), Some("x"), None, Some("y")]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch)
+            .with_method_name("parse_int")
+            .with_error_policy(ErrorPolicy::FailListing(10));
+        let result = processor
+            .process_unary::<&str, Result<i64, std::num::ParseIntError>, _>("output", |s| {
+                s.parse()
+            });
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.starts_with("parse_int failed for 2 rows"));
+        assert!(message.contains("row 1 with input (x)"));
+        assert!(message.contains("row 3 with input (y)"));
+    }
+
+    #[test]
+    fn test_process_fallible_dictionary_reports_batch_row() {
+        let batch = dictionary_batch();
+
+        let processor = UDFProcessor::new(&batch).deterministic();
+        let result = processor.process_unary::<&str, Result<i64, String>, _>("output", |s| {
+            if s == "b" {
+                Err("no b".to_string())
+            } else {
+                Ok(1)
+            }
+        });
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "output failed at row 2 with input (b): no b"
+        );
+
+        let listing = processor
+            .with_error_policy(ErrorPolicy::FailListing(10))
+            .process_unary::<&str, Result<i64, String>, _>("output", |s| Err(format!("no {}", s)));
+        let message = listing.unwrap_err().to_string();
+        assert!(message.starts_with("output failed for 2 rows"));
+        assert!(message.contains("row 0 with input (a): no a"));
+        assert!(message.contains("row 2 with input (b): no b"));
+    }
+
+    #[test]
+    fn test_process_columnar_checks_length_and_type() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "input",
+            DataType::Int64,
+            true,
+        )]));
+        let input_array = Int64Array::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let short = UDFProcessor::new(&batch)
+            .with_method_name("head")
+            .process_columnar("output", |columns| {
+                Ok::<_, ArrowError>(columns[0].slice(0, 2))
+            });
+        assert_eq!(
+            short.unwrap_err().to_string(),
+            "head returned 2 rows for a batch of 3 rows"
+        );
+
+        let mismatched = UDFProcessor::new(&batch)
+            .with_output_type(DataType::Utf8)
+            .process_columnar("output", |columns| Ok::<_, ArrowError>(columns[0].clone()));
+        assert_eq!(
+            mismatched.unwrap_err().to_string(),
+            "output returned Int64 but the output type is Utf8: \
+             the conversion may lose data and requires lenient coercion"
+        );
+
+        let doubled = UDFProcessor::new(&batch)
+            .with_output_type(DataType::Int64)
+            .process_columnar("output", |columns| {
+                arrow::compute::kernels::numeric::mul(&columns[0], &Int64Array::new_scalar(2))
+            });
+        let output_batch = doubled.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(output_batch.schema().field(0).name(), "output");
+        assert_eq!(output_array.values(), &[2, 4, 6]);
+    }
+
+    #[test]
+    fn test_process_output_field_casts_with_coercion() {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+        let input_array = Int64Array::from(vec![Some(1), Some(i64::from(i32::MAX))]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+        let output_field = Field::new("output", DataType::Int32, true);
+
+        let strict = UDFProcessor::new(&batch)
+            .with_output_field(&output_field)
+            .process_unary::<i64, i64, _>("output", |n| n);
+        assert_eq!(
+            strict.unwrap_err().to_string(),
+            "output returned Int64 but the output type is Int32: \
+             the conversion may lose data and requires lenient coercion"
+        );
+
+        let lenient = UDFProcessor::new(&batch)
+            .with_output_field(&output_field)
+            .with_coercion(Coercion::Lenient);
+        let output_batch = lenient
+            .process_unary::<i64, i64, _>("output", |n| n)
+            .unwrap();
+        assert_eq!(output_batch.schema().field(0), &output_field);
+        assert_eq!(
+            output_batch.column(0).as_primitive::<Int32Type>().values(),
+            &[1, i32::MAX]
+        );
+
+        let overflow = lenient.process_unary::<i64, i64, _>("output", |n| n + 1);
+        assert!(overflow
+            .unwrap_err()
+            .to_string()
+            .starts_with("output returned Int64 but the output type is Int32: "));
+    }
+
+    #[test]
+    fn test_process_output_field_casts_unwritable_type() {
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("42"), None]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+        let output_field = Field::new("parsed", DataType::Int64, true);
+
+        let strict = UDFProcessor::new(&batch)
+            .with_output_field(&output_field)
+            .process_unary::<String, String, _>("parsed", |s| s);
+        assert!(strict
+            .unwrap_err()
+            .to_string()
+            .starts_with("parsed returned Utf8 but the output type is Int64: "));
+
+        let output_batch = UDFProcessor::new(&batch)
+            .with_output_field(&output_field)
+            .with_coercion(Coercion::Lenient)
+            .process_unary::<String, String, _>("parsed", |s| s)
+            .unwrap();
+        assert_eq!(output_batch.schema().field(0), &output_field);
+        let parsed = output_batch.column(0).as_primitive::<Int64Type>();
+        assert_eq!(parsed.value(0), 42);
+        assert!(parsed.is_null(1));
+    }
+
+    #[tokio::test]
+    async fn test_process_async_bounded_and_in_order() {
+        use std::cell::Cell;
+
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+        let input_array = Int64Array::from(vec![Some(3), None, Some(1), Some(2), Some(0)]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let in_flight = Cell::new(0);
+        let max_in_flight = Cell::new(0);
+        let output_batch = UDFProcessor::new(&batch)
+            .with_concurrency(2)
+            .process_unary_async::<i64, String, _, _>("delayed", |n| {
+                let in_flight = &in_flight;
+                let max_in_flight = &max_in_flight;
+                async move {
+                    in_flight.set(in_flight.get() + 1);
+                    max_in_flight.set(max_in_flight.get().max(in_flight.get()));
+                    // Larger values finish later
+                    for _ in 0..n {
+                        tokio::task::yield_now().await;
+                    }
+                    in_flight.set(in_flight.get() - 1);
+                    format!("n={}", n)
+                }
+            })
+            .await
+            .unwrap();
+
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(output_array.value(0), "n=3");
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(2), "n=1");
+        assert_eq!(output_array.value(3), "n=2");
+        assert_eq!(output_array.value(4), "n=0");
+        assert_eq!(max_in_flight.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_process_async_fallible_reports_row() {
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec!["1", "x"]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let error = UDFProcessor::new(&batch)
+            .process_unary_async::<String, Result<i64, std::num::ParseIntError>, _, _>(
+                "parse",
+                |s| async move { s.parse::<i64>() },
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "parse failed at row 1 with input (x): invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_process_memoized_per_batch() {
+        use std::cell::Cell;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a = StringArray::from(vec![Some("x"), Some("x"), Some("y"), None, Some("x")]);
+        let b = Int64Array::from(vec![Some(1), Some(1), Some(1), Some(1), Some(2)]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(a), Arc::new(b)]).unwrap();
+
+        let calls = Cell::new(0);
+        let output_batch = UDFProcessor::new(&batch)
+            .with_memoization(Memoization::Batch)
+            .process_binary::<&str, i64, String, _>("repeat", |s, n| {
+                calls.set(calls.get() + 1);
+                s.repeat(n as usize)
+            })
+            .unwrap();
+
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(output_array.value(0), "x");
+        assert_eq!(output_array.value(1), "x");
+        assert_eq!(output_array.value(2), "y");
+        assert!(output_array.is_null(3));
+        assert_eq!(output_array.value(4), "xx");
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_process_memoized_across_batches() {
+        use std::cell::Cell;
+
+        let batch = |values: Vec<i64>| {
+            let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+            RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(values))]).unwrap()
+        };
+        let calls = Cell::new(0);
+        let repeat = |n: i64| {
+            calls.set(calls.get() + 1);
+            n.to_string().repeat(2)
+        };
+
+        for values in [vec![1, 2, 1], vec![2, 3, 2, 1]] {
+            UDFProcessor::new(&batch(values))
+                .with_method_name("test_process_memoized_across_batches")
+                .with_memoization(Memoization::Lru(10))
+                .process_unary::<i64, String, _>("output", repeat)
+                .unwrap();
+        }
+        assert_eq!(calls.get(), 3);
+
+        let output_batch = UDFProcessor::new(&batch(vec![3, 4, 1]))
+            .with_method_name("test_process_memoized_across_batches")
+            .with_memoization(Memoization::Lru(10))
+            .process_unary::<i64, String, _>("output", repeat)
+            .unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(output_array.value(0), "33");
+        assert_eq!(output_array.value(1), "44");
+        assert_eq!(output_array.value(2), "11");
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn test_process_memoized_across_batches_per_method_name() {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+        let array = Int64Array::from(vec![1, 2]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+        let double = |n: i64| n * 2;
+        let negate = |n: i64| -n;
+
+        let unnamed = UDFProcessor::new(&batch)
+            .with_memoization(Memoization::Lru(10))
+            .process_unary::<i64, i64, _>("output", double);
+        assert_eq!(
+            unnamed.unwrap_err().to_string(),
+            "output uses Memoization::Lru, which requires a method name set with with_method_name"
+        );
+
+        let run = |method_name: &str, user_fn: fn(i64) -> i64| {
+            let output_batch = UDFProcessor::new(&batch)
+                .with_method_name(method_name)
+                .with_memoization(Memoization::Lru(10))
+                .process_unary::<i64, i64, _>("output", user_fn)
+                .unwrap();
+            output_batch
+                .column(0)
+                .as_primitive::<Int64Type>()
+                .values()
+                .to_vec()
+        };
+        for _ in 0..2 {
+            assert_eq!(run("test_process_memoized_double", double), [2, 4]);
+            assert_eq!(run("test_process_memoized_negate", negate), [-1, -2]);
+        }
+    }
+
+    #[test]
+    fn test_process_memoized_reports_batch_row() {
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+        let parse = |values: Vec<&str>| {
+            let array = StringArray::from(values);
+            let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+            UDFProcessor::new(&batch)
+                .with_method_name("test_process_memoized_reports_batch_row")
+                .with_memoization(Memoization::Lru(10))
+                .process_unary::<&str, Result<i64, std::num::ParseIntError>, _>("n", |s| {
+                    s.parse::<i64>()
+                })
+        };
+
+        parse(vec!["1", "1", "2"]).unwrap();
+        let error = parse(vec!["2", "1", "1", "x"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "test_process_memoized_reports_batch_row failed at row 3 with input (x): invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_process_memoized_skips_failed_rows() {
+        use std::cell::Cell;
+
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+        let array = StringArray::from(vec!["1", "x"]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+        let calls = Cell::new(0);
+        let parse = |s: &str| {
+            calls.set(calls.get() + 1);
+            s.parse::<i64>()
+        };
+
+        for _ in 0..2 {
+            let output_batch = UDFProcessor::new(&batch)
+                .with_method_name("test_process_memoized_skips_failed_rows")
+                .with_memoization(Memoization::Lru(10))
+                .with_error_policy(ErrorPolicy::Null)
+                .process_unary::<&str, Result<i64, std::num::ParseIntError>, _>("n", parse)
+                .unwrap();
+            assert!(output_batch.column(0).is_null(1));
+        }
+        // "1" is served from the cache, the failed "x" is evaluated again
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_process_memoized_per_identity() {
+        use crate::{Identity, UdfContext};
+
+        let context = |principal: &str| UdfContext {
+            identity: Identity {
+                id: None,
+                principal: Some(principal.to_string()),
+                account: None,
+                arn: None,
+            },
+            method_name: "tag".to_string(),
+            function_type: "SCALAR".to_string(),
+            a_id: "a1".to_string(),
+            lambda: lambda_runtime::Context::default(),
+        };
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+        let array = StringArray::from(vec!["query"]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap();
+
+        for principal in ["alice", "bob"] {
+            let context = context(principal);
+            let output_batch = UDFProcessor::new(&batch)
+                .with_method_name("test_process_memoized_per_identity")
+                .with_memoization(Memoization::Lru(10))
+                .with_context(&context)
+                .process_unary::<&str, String, _>("output", |s| {
+                    format!("{}@{}", s, context.identity.principal.as_deref().unwrap())
+                })
+                .unwrap();
+            let output_array = output_batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            assert_eq!(output_array.value(0), format!("query@{}", principal));
+        }
+    }
+
+    fn names_and_titles() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("title", DataType::Utf8, true),
+        ]));
+        let name = StringArray::from(vec![Some("Ada"), None, Some("Alan"), None]);
+        let title = StringArray::from(vec![Some("Dr."), Some("Prof."), None, None]);
+        RecordBatch::try_new(schema, vec![Arc::new(name), Arc::new(title)]).unwrap()
+    }
+
+    fn greet(name: Option<&str>, title: Option<&str>) -> String {
+        format!("{} {}", title.unwrap_or("-"), name.unwrap_or("-"))
+    }
+
+    #[test]
+    fn test_process_returns_null_on_null_input() {
+        let batch = names_and_titles();
+
+        let output_batch = UDFProcessor::new(&batch)
+            .with_null_handling(NullHandling::ReturnsNullOnNullInput)
+            .process_binary::<Option<&str>, Option<&str>, String, _>("greeting", greet)
+            .unwrap();
+
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(output_array.value(0), "Dr. Ada");
+        assert_eq!(output_array.null_count(), 3);
+    }
+
+    #[test]
+    fn test_process_called_on_null_input() {
+        let batch = names_and_titles();
+
+        let output_batch = UDFProcessor::new(&batch)
+            .with_null_handling(NullHandling::CalledOnNullInput)
+            .process_binary::<Option<&str>, Option<&str>, String, _>("greeting", greet)
+            .unwrap();
+
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(output_array.value(1), "Prof. -");
+        assert_eq!(output_array.value(2), "- Alan");
+        assert_eq!(output_array.value(3), "- -");
+
+        let error = UDFProcessor::new(&batch)
+            .with_method_name("greeting")
+            .with_null_handling(NullHandling::CalledOnNullInput)
+            .process_binary::<Option<&str>, &str, String, _>("greeting", |name, title| {
+                greet(name, Some(title))
+            })
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "greeting is called on null input, but argument 2 is not an Option"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_async_returns_null_on_null_input() {
+        let batch = names_and_titles();
+
+        let output_batch = UDFProcessor::new(&batch)
+            .with_null_handling(NullHandling::ReturnsNullOnNullInput)
+            .process_binary_async::<String, Option<String>, String, _, _>(
+                "greeting",
+                |name, title| async move { greet(Some(&name), title.as_deref()) },
+            )
+            .await
+            .unwrap();
+
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(output_array.value(0), "Dr. Ada");
+        assert!(output_array.is_null(2));
+    }
+
+    #[test]
+    fn test_process_panic_reports_row() {
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("abc"), None, Some("a")]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let error = UDFProcessor::new(&batch)
+            .with_method_name("second_char")
+            .with_error_policy(ErrorPolicy::Null)
+            .process_unary::<&str, String, _>("output", |s| s[1..2].to_string())
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("second_char panicked at row 2 with input (a): "));
+
+        // The processor stays usable after a panic
+        let output_batch = UDFProcessor::new(&batch)
+            .process_unary::<&str, i64, _>("output", |s| s.len() as i64)
+            .unwrap();
+        assert_eq!(output_batch.num_rows(), 3);
+    }
+
+    #[test]
+    fn test_process_kernel_panic_reports_row() {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+        let input_array = Int64Array::from(vec![Some(4), None, Some(0), Some(2)]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let error = UDFProcessor::new(&batch)
+            .with_method_name("inverse")
+            .process_unary::<i64, i64, _>("output", |n| 100 / n)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "inverse panicked at row 2 with input (0): attempt to divide by zero"
+        );
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a_array = Int64Array::from(vec![Some(1), None, Some(3), Some(4)]);
+        let b_array = Int64Array::from(vec![Some(1), Some(0), Some(1), Some(0)]);
+        let batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        let error = UDFProcessor::new(&batch)
+            .with_method_name("quotient")
+            .process_binary::<i64, i64, i64, _>("output", |a, b| a / b)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "quotient panicked at row 3 with input (4, 0): attempt to divide by zero"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_async_panic_reports_row() {
+        let schema = Arc::new(Schema::new(vec![Field::new("n", DataType::Int64, true)]));
+        let input_array = Int64Array::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let error = UDFProcessor::new(&batch)
+            .with_method_name("lookup")
+            .process_unary_async::<i64, i64, _, _>("output", |n| async move {
+                if n == 2 {
+                    panic!("lookup of {} failed", n);
+                }
+                n
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "lookup panicked at row 1 with input (2): lookup of 2 failed"
+        );
+    }
 }
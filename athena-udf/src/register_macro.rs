@@ -7,7 +7,11 @@
 ///
 /// ```ignore
 /// athena_udf_handler! {
+///     #[option(args...)]
 ///     "function_name" => function_ident: (InputType1, InputType2, ...) -> OutputType,
+///     "variadic_function_name" => variadic_function_ident: [InputType] -> OutputType,
+///     "async_function_name" => async_function_ident: async (InputType1, ...) -> OutputType,
+///     "columnar_function_name" => columnar_function_ident: columnar,
 ///     ...
 /// }
 /// ```
@@ -18,6 +22,34 @@
 /// 3. Select the appropriate process method (process_unary, process_binary, etc.)
 /// 4. Generate the match arm with correct type parameters
 ///
+/// Each function can be preceded by options written as attributes. An option calls
+/// the [`UDFProcessor`](crate::UDFProcessor) builder method of the same name with the
+/// given arguments, e.g. `#[with_coercion(Coercion::Lenient)]` or `#[deterministic]`.
+/// With the `rayon` feature, `#[parallel(min_rows)]` processes batches of at least
+/// `min_rows` rows on multiple threads.
+///
+/// Functions with up to 12 parameters can be registered with their signature.
+/// Functions whose first parameter is `&UdfContext` receive the
+/// [`UdfContext`](crate::UdfContext) of the request in addition to the input values;
+/// the context is not part of the signature seen by Athena.
+/// Functions registered as `[T]` take any number of arguments of type `T` and
+/// receive them as `&[T]` or `Vec<T>`; they are run with
+/// [`UDFProcessor::process_variadic`](crate::UDFProcessor::process_variadic).
+///
+/// The handler can start with a `state: S = init;` header, where `init` is a
+/// function returning `Result<S, E>`. The state is initialized on the first
+/// request of the Lambda container, or earlier by calling the generated
+/// `init_state()` in `main`, and is passed to functions whose first parameter is
+/// `&State<S>`. Initialization errors fail every request, including Athena's ping,
+/// until an initialization succeeds.
+///
+/// Functions registered as `async (...)` return a future and are awaited for up to
+/// [`with_concurrency`](crate::UDFProcessor::with_concurrency) rows at once. They
+/// need an async context, which `athena_udf_handler!` provides.
+///
+/// Functions registered as `columnar` receive the whole input columns and are run
+/// with [`UDFProcessor::process_columnar`](crate::UDFProcessor::process_columnar).
+///
 /// # Examples
 ///
 /// ```ignore
@@ -33,6 +65,7 @@
 ///
 /// athena_udf_handler! {
 ///     "string_reverse" => string_reverse: (String) -> String,
+///     #[with_coercion(Coercion::Lenient)]
 ///     "add_numbers" => add_numbers: (i64, i64) -> i64,
 /// }
 ///
@@ -47,59 +80,41 @@
 /// ```
 #[macro_export]
 macro_rules! athena_udf_handler {
-    (
-        $( $name:literal => $fn:ident : ( $($input:ty),+ ) -> $output:ty ),+ $(,)?
-    ) => {
+    ( state: $state_type:ty = $init:expr; $($entries:tt)+ ) => {
+        static ATHENA_UDF_STATE: $crate::LazyState<$state_type> =
+            $crate::LazyState::new(|| ($init)().map_err(::core::convert::Into::into));
+
+        /// Initializes the UDF state ahead of the first request, e.g. in `main`.
+        #[allow(dead_code)]
+        fn init_state() -> Result<(), lambda_runtime::Error> {
+            ATHENA_UDF_STATE.get().map(|_| ())
+        }
+
         async fn function_handler(
             event: $crate::LambdaEvent<$crate::Value>
         ) -> Result<$crate::Value, lambda_runtime::Error> {
-            $crate::handle_athena_request(event, |input_batch, method_name, output_col_name| {
-                match method_name {
-                    $(
-                        $name => {
-                            $crate::athena_udf_handler!(@process input_batch, output_col_name, $fn, ($($input),+), $output)
-                        }
-                    )+
-                    _ => Err(format!("Unknown function: {}", method_name).into()),
-                }
+            let state = ATHENA_UDF_STATE.get()?;
+            $crate::handle_athena_request_async(event, |input_batch, context, output_field| async move {
+                $crate::register_udfs!(
+                    &input_batch, context.method_name.as_str(), &output_field, &context, state => {
+                        $($entries)+
+                    }
+                )
             }).await
         }
     };
-
-    // Process unary functions (1 input)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_unary::<$i1, $output, _>($output_col, $fn)
-    };
-
-    // Process binary functions (2 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_binary::<$i1, $i2, $output, _>($output_col, $fn)
-    };
-
-    // Process ternary functions (3 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_ternary::<$i1, $i2, $i3, $output, _>($output_col, $fn)
-    };
-
-    // Process quaternary functions (4 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_quaternary::<$i1, $i2, $i3, $i4, $output, _>($output_col, $fn)
-    };
-
-    // Process quinary functions (5 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_quinary::<$i1, $i2, $i3, $i4, $i5, $output, _>($output_col, $fn)
-    };
-
-    // Process senary functions (6 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_senary::<$i1, $i2, $i3, $i4, $i5, $i6, $output, _>($output_col, $fn)
+    ( $($entries:tt)+ ) => {
+        async fn function_handler(
+            event: $crate::LambdaEvent<$crate::Value>
+        ) -> Result<$crate::Value, lambda_runtime::Error> {
+            $crate::handle_athena_request_async(event, |input_batch, context, output_field| async move {
+                $crate::register_udfs!(
+                    &input_batch, context.method_name.as_str(), &output_field, &context => {
+                        $($entries)+
+                    }
+                )
+            }).await
+        }
     };
 }
 
@@ -109,12 +124,28 @@ macro_rules! athena_udf_handler {
 /// or when you want to add custom logic before/after UDF dispatch.
 ///
 /// For most cases, prefer `athena_udf_handler!` which generates the complete handler.
+/// Both macros accept the same entries and per-function options.
 ///
 /// # Syntax
 ///
 /// ```ignore
-/// register_udfs!(input_batch, method_name, output_col_name => {
+/// register_udfs!(input_batch, method_name, output_field => {
+///     #[option(args...)]
 ///     "function_name" => function_ident: (InputType1, InputType2, ...) -> OutputType,
+///     "variadic_function_name" => variadic_function_ident: [InputType] -> OutputType,
+///     "columnar_function_name" => columnar_function_ident: columnar,
+///     ...
+/// })
+///
+/// // Functions taking `&UdfContext` need the context as a fourth argument
+/// register_udfs!(input_batch, context.method_name.as_str(), output_field, context => {
+///     "function_name" => function_ident: (&UdfContext, InputType1, ...) -> OutputType,
+///     ...
+/// })
+///
+/// // Functions taking `&State<S>` need the state as a fifth argument
+/// register_udfs!(input_batch, context.method_name.as_str(), output_field, context, state => {
+///     "function_name" => function_ident: (&State<S>, InputType1, ...) -> OutputType,
 ///     ...
 /// })
 /// ```
@@ -122,61 +153,372 @@ macro_rules! athena_udf_handler {
 macro_rules! register_udfs {
     // Entry point: processes all function registrations
     (
-        $batch:expr, $method:expr, $output_col:expr => {
-            $( $name:literal => $fn:ident : ( $($input:ty),+ ) -> $output:ty ),+ $(,)?
-        }
+        $batch:expr, $method:expr, $output_field:expr $(, $context:expr $(, $state:expr)?)? => { $($entries:tt)+ }
     ) => {
+        $crate::register_udfs!(
+            @munch ($batch, $method, $output_field, [$($context)?], [$($($state)?)?]) [] $($entries)+
+        )
+    };
+
+    // All entries processed: emit the dispatch
+    (@munch ($batch:expr, $method:expr, $output_field:expr, $context:tt, $state:tt) [$($arms:tt)*] $(,)?) => {
         match $method {
-            $(
-                $name => {
-                    $crate::register_udfs!(@process $batch, $output_col, $fn, ($($input),+), $output)
-                }
-            )+
+            $($arms)*
             _ => Err(format!("Unknown function: {}", $method).into()),
         }
     };
 
-    // Process unary functions (1 input)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty), $output:ty) => {
+    // Columnar function over whole input arrays
+    (
+        @munch ($batch:expr, $method:expr, $output_field:expr, $context:tt, $state:tt) [$($arms:tt)*]
+        $( #[$opt:ident $( ( $($arg:expr),* $(,)? ) )?] )*
+        $name:literal => $fn:ident : columnar
+        $(, $($rest:tt)*)?
+    ) => {
+        $crate::register_udfs!(@munch ($batch, $method, $output_field, $context, $state) [
+            $($arms)*
+            $name => {
+                $crate::register_udfs!(@processor $batch, $output_field, $name $(, $opt($($($arg),*)?))*)
+                    .process_columnar($output_field.name(), $fn)
+            }
+        ] $($($rest)*)?)
+    };
+
+    // Variadic function over all input columns of one type
+    (
+        @munch ($batch:expr, $method:expr, $output_field:expr, $context:tt, $state:tt) [$($arms:tt)*]
+        $( #[$opt:ident $( ( $($arg:expr),* $(,)? ) )?] )*
+        $name:literal => $fn:ident : [ $input:ty ] -> $output:ty
+        $(, $($rest:tt)*)?
+    ) => {
+        $crate::register_udfs!(@munch ($batch, $method, $output_field, $context, $state) [
+            $($arms)*
+            $name => {
+                $crate::register_udfs!(@processor $batch, $output_field, $name $(, $opt($($($arg),*)?))*)
+                    .process_variadic::<$input, $output, _>(
+                        $output_field.name(),
+                        |values| $fn(::core::convert::Into::into(values)),
+                    )
+            }
+        ] $($($rest)*)?)
+    };
+
+    // Async row-wise function, awaited with bounded concurrency
+    (
+        @munch ($batch:expr, $method:expr, $output_field:expr, $context:tt, $state:tt) [$($arms:tt)*]
+        $( #[$opt:ident $( ( $($arg:expr),* $(,)? ) )?] )*
+        $name:literal => $fn:ident : async ( $($input:ty),+ ) -> $output:ty
+        $(, $($rest:tt)*)?
+    ) => {
+        $crate::register_udfs!(@munch ($batch, $method, $output_field, $context, $state) [
+            $($arms)*
+            $name => {
+                let processor = $crate::register_udfs!(
+                    @processor $batch, $output_field, $name $(, $opt($($($arg),*)?))*
+                );
+                $crate::register_udfs!(@process_async processor, $output_field, $fn, ($($input),+), $output)
+            }
+        ] $($($rest)*)?)
+    };
+
+    // Row-wise function receiving the request context as its first argument
+    (
+        @munch ($batch:expr, $method:expr, $output_field:expr, $context:tt, $state:tt) [$($arms:tt)*]
+        $( #[$opt:ident $( ( $($arg:expr),* $(,)? ) )?] )*
+        $name:literal => $fn:ident : ( &UdfContext, $($input:ty),+ ) -> $output:ty
+        $(, $($rest:tt)*)?
+    ) => {
+        $crate::register_udfs!(@munch ($batch, $method, $output_field, $context, $state) [
+            $($arms)*
+            $name => {
+                let processor = $crate::register_udfs!(
+                    @processor $batch, $output_field, $name $(, $opt($($($arg),*)?))*
+                );
+                let context: &$crate::UdfContext = $crate::register_udfs!(@context $context);
+                let processor = processor.with_context(context);
+                $crate::register_udfs!(@process processor, $output_field, $fn, ($($input),+), $output, context)
+            }
+        ] $($($rest)*)?)
+    };
+
+    // Row-wise function receiving the shared state as its first argument
+    (
+        @munch ($batch:expr, $method:expr, $output_field:expr, $context:tt, $state:tt) [$($arms:tt)*]
+        $( #[$opt:ident $( ( $($arg:expr),* $(,)? ) )?] )*
+        $name:literal => $fn:ident : ( &State<$state_type:ty>, $($input:ty),+ ) -> $output:ty
+        $(, $($rest:tt)*)?
+    ) => {
+        $crate::register_udfs!(@munch ($batch, $method, $output_field, $context, $state) [
+            $($arms)*
+            $name => {
+                let processor = $crate::register_udfs!(
+                    @processor $batch, $output_field, $name $(, $opt($($($arg),*)?))*
+                );
+                let state: &$crate::State<$state_type> = $crate::register_udfs!(@state $state);
+                $crate::register_udfs!(@process processor, $output_field, $fn, ($($input),+), $output, state)
+            }
+        ] $($($rest)*)?)
+    };
+
+    // Row-wise function with a fixed signature
+    (
+        @munch ($batch:expr, $method:expr, $output_field:expr, $context:tt, $state:tt) [$($arms:tt)*]
+        $( #[$opt:ident $( ( $($arg:expr),* $(,)? ) )?] )*
+        $name:literal => $fn:ident : ( $($input:ty),+ ) -> $output:ty
+        $(, $($rest:tt)*)?
+    ) => {
+        $crate::register_udfs!(@munch ($batch, $method, $output_field, $context, $state) [
+            $($arms)*
+            $name => {
+                let processor = $crate::register_udfs!(
+                    @processor $batch, $output_field, $name $(, $opt($($($arg),*)?))*
+                );
+                $crate::register_udfs!(@process processor, $output_field, $fn, ($($input),+), $output)
+            }
+        ] $($($rest)*)?)
+    };
+
+    // The request context passed to `register_udfs!`
+    (@context [$context:expr]) => {
+        $context
+    };
+    (@context []) => {
+        compile_error!("functions taking `&UdfContext` need a context argument: register_udfs!(batch, method, output_field, context => { ... })")
+    };
+
+    // The shared state passed to `register_udfs!`
+    (@state [$state:expr]) => {
+        $state
+    };
+    (@state []) => {
+        compile_error!("functions taking `&State<S>` need a state argument: register_udfs!(batch, method, output_field, context, state => { ... })")
+    };
+
+    // Build the processor for one function, applying its options
+    (@processor $batch:expr, $output_field:expr, $name:literal $(, $opt:ident($($arg:expr),*))*) => {
         $crate::UDFProcessor::new($batch)
-            .process_unary::<$i1, $output, _>($output_col, $fn)
+            .with_output_field(&$output_field)
+            .with_method_name($name)
+            $( .$opt($($arg),*) )*
+    };
+
+    // Process unary functions (1 input)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty), $output:ty) => {
+        $processor.process_unary::<$i1, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty), $output:ty, $context:ident) => {
+        $processor.process_unary::<$i1, $output, _>($output_field.name(), |a1| $fn($context, a1))
     };
 
     // Process binary functions (2 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_binary::<$i1, $i2, $output, _>($output_col, $fn)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty), $output:ty) => {
+        $processor.process_binary::<$i1, $i2, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty), $output:ty, $context:ident) => {
+        $processor.process_binary::<$i1, $i2, $output, _>(
+            $output_field.name(),
+            |a1, a2| $fn($context, a1, a2),
+        )
     };
 
     // Process ternary functions (3 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_ternary::<$i1, $i2, $i3, $output, _>($output_col, $fn)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty), $output:ty) => {
+        $processor.process_ternary::<$i1, $i2, $i3, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty), $output:ty, $context:ident) => {
+        $processor.process_ternary::<$i1, $i2, $i3, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3| $fn($context, a1, a2, a3),
+        )
     };
 
     // Process quaternary functions (4 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_quaternary::<$i1, $i2, $i3, $i4, $output, _>($output_col, $fn)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty), $output:ty) => {
+        $processor.process_quaternary::<$i1, $i2, $i3, $i4, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty), $output:ty, $context:ident) => {
+        $processor.process_quaternary::<$i1, $i2, $i3, $i4, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4| $fn($context, a1, a2, a3, a4),
+        )
     };
 
     // Process quinary functions (5 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_quinary::<$i1, $i2, $i3, $i4, $i5, $output, _>($output_col, $fn)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty), $output:ty) => {
+        $processor.process_quinary::<$i1, $i2, $i3, $i4, $i5, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty), $output:ty, $context:ident) => {
+        $processor.process_quinary::<$i1, $i2, $i3, $i4, $i5, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4, a5| $fn($context, a1, a2, a3, a4, a5),
+        )
     };
 
     // Process senary functions (6 inputs)
-    (@process $batch:expr, $output_col:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty), $output:ty) => {
-        $crate::UDFProcessor::new($batch)
-            .process_senary::<$i1, $i2, $i3, $i4, $i5, $i6, $output, _>($output_col, $fn)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty), $output:ty) => {
+        $processor.process_senary::<$i1, $i2, $i3, $i4, $i5, $i6, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty), $output:ty, $context:ident) => {
+        $processor.process_senary::<$i1, $i2, $i3, $i4, $i5, $i6, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4, a5, a6| $fn($context, a1, a2, a3, a4, a5, a6),
+        )
+    };
+
+    // Process septenary functions (7 inputs)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty), $output:ty) => {
+        $processor.process_septenary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty), $output:ty, $context:ident) => {
+        $processor.process_septenary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4, a5, a6, a7| $fn($context, a1, a2, a3, a4, a5, a6, a7),
+        )
+    };
+
+    // Process octonary functions (8 inputs)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty), $output:ty) => {
+        $processor.process_octonary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty), $output:ty, $context:ident) => {
+        $processor.process_octonary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4, a5, a6, a7, a8| $fn($context, a1, a2, a3, a4, a5, a6, a7, a8),
+        )
+    };
+
+    // Process nonary functions (9 inputs)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty), $output:ty) => {
+        $processor.process_nonary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty), $output:ty, $context:ident) => {
+        $processor.process_nonary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4, a5, a6, a7, a8, a9| $fn($context, a1, a2, a3, a4, a5, a6, a7, a8, a9),
+        )
+    };
+
+    // Process denary functions (10 inputs)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty), $output:ty) => {
+        $processor.process_denary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty), $output:ty, $context:ident) => {
+        $processor.process_denary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4, a5, a6, a7, a8, a9, a10| $fn($context, a1, a2, a3, a4, a5, a6, a7, a8, a9, a10),
+        )
+    };
+
+    // Process undenary functions (11 inputs)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty, $i11:ty), $output:ty) => {
+        $processor.process_undenary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $i11, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty, $i11:ty), $output:ty, $context:ident) => {
+        $processor.process_undenary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $i11, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11| $fn($context, a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11),
+        )
+    };
+
+    // Process duodenary functions (12 inputs)
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty, $i11:ty, $i12:ty), $output:ty) => {
+        $processor.process_duodenary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $i11, $i12, $output, _>($output_field.name(), $fn)
+    };
+    (@process $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty, $i11:ty, $i12:ty), $output:ty, $context:ident) => {
+        $processor.process_duodenary::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $i11, $i12, $output, _>(
+            $output_field.name(),
+            |a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11, a12| $fn($context, a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11, a12),
+        )
+    };
+
+    // Process async unary functions (1 input)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty), $output:ty) => {
+        $processor
+            .process_unary_async::<$i1, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async binary functions (2 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty), $output:ty) => {
+        $processor
+            .process_binary_async::<$i1, $i2, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async ternary functions (3 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty), $output:ty) => {
+        $processor
+            .process_ternary_async::<$i1, $i2, $i3, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async quaternary functions (4 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty), $output:ty) => {
+        $processor
+            .process_quaternary_async::<$i1, $i2, $i3, $i4, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async quinary functions (5 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty), $output:ty) => {
+        $processor
+            .process_quinary_async::<$i1, $i2, $i3, $i4, $i5, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async senary functions (6 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty), $output:ty) => {
+        $processor
+            .process_senary_async::<$i1, $i2, $i3, $i4, $i5, $i6, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async septenary functions (7 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty), $output:ty) => {
+        $processor
+            .process_septenary_async::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async octonary functions (8 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty), $output:ty) => {
+        $processor
+            .process_octonary_async::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async nonary functions (9 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty), $output:ty) => {
+        $processor
+            .process_nonary_async::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async denary functions (10 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty), $output:ty) => {
+        $processor
+            .process_denary_async::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async undenary functions (11 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty, $i11:ty), $output:ty) => {
+        $processor
+            .process_undenary_async::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $i11, $output, _, _>($output_field.name(), $fn)
+            .await
+    };
+
+    // Process async duodenary functions (12 inputs)
+    (@process_async $processor:expr, $output_field:expr, $fn:ident, ($i1:ty, $i2:ty, $i3:ty, $i4:ty, $i5:ty, $i6:ty, $i7:ty, $i8:ty, $i9:ty, $i10:ty, $i11:ty, $i12:ty), $output:ty) => {
+        $processor
+            .process_duodenary_async::<$i1, $i2, $i3, $i4, $i5, $i6, $i7, $i8, $i9, $i10, $i11, $i12, $output, _, _>($output_field.name(), $fn)
+            .await
     };
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::*;
-    use arrow::array::{Int64Array, StringArray};
+    use arrow::array::{Array, Int64Array, StringArray};
     use arrow::datatypes::{DataType, Field, Schema};
     use arrow::record_batch::RecordBatch;
     use std::sync::Arc;
@@ -193,6 +535,62 @@ mod tests {
         format!("{}{}{}", a, b, c)
     }
 
+    fn byte_length(s: &str) -> i64 {
+        s.len() as i64
+    }
+
+    fn parse_int(s: &str) -> Result<i64, std::num::ParseIntError> {
+        s.parse()
+    }
+
+    fn coalesce_all(values: &[Option<i64>]) -> Option<i64> {
+        values.iter().copied().flatten().next()
+    }
+
+    fn join_all(values: Vec<&str>) -> String {
+        values.join("-")
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn score(
+        a: i64,
+        b: i64,
+        c: i64,
+        d: i64,
+        e: i64,
+        f: i64,
+        g: i64,
+        h: i64,
+        i: i64,
+        j: i64,
+        k: i64,
+        l: f64,
+    ) -> f64 {
+        (a + b + c + d + e + f + g + h + i + j + k) as f64 * l
+    }
+
+    fn tag_principal(context: &crate::UdfContext, s: &str) -> String {
+        format!(
+            "{}@{}",
+            s,
+            context.identity.principal.as_deref().unwrap_or("?")
+        )
+    }
+
+    fn has_prefix(prefixes: &crate::State<Vec<String>>, s: &str) -> bool {
+        prefixes.iter().any(|prefix| s.starts_with(prefix.as_str()))
+    }
+
+    async fn lookup_name(id: i64) -> String {
+        format!("user-{}", id)
+    }
+
+    fn add_columns(
+        columns: &[arrow::array::ArrayRef],
+    ) -> Result<arrow::array::ArrayRef, arrow::error::ArrowError> {
+        arrow::compute::kernels::numeric::add(&columns[0], &columns[1])
+    }
+
     #[test]
     fn test_register_udfs_unary() {
         let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
@@ -200,9 +598,9 @@ mod tests {
         let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
 
         let method_name = "string_reverse";
-        let output_col_name = "output";
+        let output_field = Field::new("output", DataType::Utf8, true);
 
-        let result = register_udfs!(&input_batch, method_name, output_col_name => {
+        let result = register_udfs!(&input_batch, method_name, &output_field => {
             "string_reverse" => string_reverse: (String) -> String,
         });
 
@@ -224,15 +622,15 @@ mod tests {
             Field::new("a", DataType::Int64, true),
             Field::new("b", DataType::Int64, true),
         ]));
-        let a_array = Int64Array::from(vec![Some(10), Some(20)]);
-        let b_array = Int64Array::from(vec![Some(5), Some(15)]);
+        let a_array = Int64Array::from(vec![Some(10), Some(20), None]);
+        let b_array = Int64Array::from(vec![Some(5), Some(15), Some(1)]);
         let input_batch =
             RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
 
         let method_name = "add_numbers";
-        let output_col_name = "output";
+        let output_field = Field::new("output", DataType::Int64, true);
 
-        let result = register_udfs!(&input_batch, method_name, output_col_name => {
+        let result = register_udfs!(&input_batch, method_name, &output_field => {
             "add_numbers" => add_numbers: (i64, i64) -> i64,
         });
 
@@ -246,6 +644,12 @@ mod tests {
 
         assert_eq!(output_array.value(0), 15);
         assert_eq!(output_array.value(1), 35);
+        assert!(output_array.is_null(2));
+        // Primitive functions run with a kernel that reuses the input null buffer
+        assert_eq!(
+            output_array.nulls().unwrap().buffer().as_ptr(),
+            input_batch.column(0).nulls().unwrap().buffer().as_ptr()
+        );
     }
 
     #[test]
@@ -255,9 +659,9 @@ mod tests {
         let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
 
         let method_name = "string_reverse";
-        let output_col_name = "output";
+        let output_field = Field::new("output", DataType::Utf8, true);
 
-        let result = register_udfs!(&input_batch, method_name, output_col_name => {
+        let result = register_udfs!(&input_batch, method_name, &output_field => {
             "string_reverse" => string_reverse: (String) -> String,
             "add_numbers" => add_numbers: (i64, i64) -> i64,
         });
@@ -272,9 +676,9 @@ mod tests {
         let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
 
         let method_name = "unknown_function";
-        let output_col_name = "output";
+        let output_field = Field::new("output", DataType::Utf8, true);
 
-        let result = register_udfs!(&input_batch, method_name, output_col_name => {
+        let result = register_udfs!(&input_batch, method_name, &output_field => {
             "string_reverse" => string_reverse: (String) -> String,
         });
 
@@ -299,9 +703,9 @@ mod tests {
         .unwrap();
 
         let method_name = "concat_three";
-        let output_col_name = "output";
+        let output_field = Field::new("output", DataType::Utf8, true);
 
-        let result = register_udfs!(&input_batch, method_name, output_col_name => {
+        let result = register_udfs!(&input_batch, method_name, &output_field => {
             "concat_three" => concat_three: (String, String, String) -> String,
         });
 
@@ -316,6 +720,524 @@ mod tests {
         assert_eq!(output_array.value(0), "Hello World");
     }
 
+    #[test]
+    fn test_register_udfs_borrowed_str() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("hello"), None]);
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let method_name = "byte_length";
+        let output_field = Field::new("output", DataType::Int64, true);
+
+        let result = register_udfs!(&input_batch, method_name, &output_field => {
+            "byte_length" => byte_length: (&str) -> i64,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 5);
+        assert!(output_array.is_null(1));
+    }
+
+    #[test]
+    fn test_register_udfs_with_options() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let a_array = StringArray::from(vec![Some("10")]);
+        let b_array = arrow::array::Int32Array::from(vec![Some(5)]);
+        let input_batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        let method_name = "add_numbers";
+        let output_field = Field::new("output", DataType::Int64, true);
+
+        let strict = register_udfs!(&input_batch, method_name, &output_field => {
+            "add_numbers" => add_numbers: (i64, i64) -> i64,
+        });
+        assert!(strict.is_err());
+
+        let lenient = register_udfs!(&input_batch, method_name, &output_field => {
+            #[with_coercion(crate::Coercion::Lenient)]
+            #[deterministic]
+            "add_numbers" => add_numbers: (i64, i64) -> i64,
+        });
+        let output_batch = lenient.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 15);
+    }
+
+    #[test]
+    fn test_register_udfs_fallible() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("7"), Some("seven")]);
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let method_name = "parse_int";
+        let output_field = Field::new("output", DataType::Int64, true);
+
+        let failed = register_udfs!(&input_batch, method_name, &output_field => {
+            "parse_int" => parse_int: (&str) -> Result<i64, std::num::ParseIntError>,
+        });
+        assert!(failed
+            .unwrap_err()
+            .to_string()
+            .starts_with("parse_int failed at row 1 with input (seven)"));
+
+        let nulled = register_udfs!(&input_batch, method_name, &output_field => {
+            #[with_error_policy(crate::ErrorPolicy::Null)]
+            "parse_int" => parse_int: (&str) -> Result<i64, std::num::ParseIntError>,
+        });
+        let output_batch = nulled.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 7);
+        assert!(output_array.is_null(1));
+    }
+
+    #[test]
+    fn test_register_udfs_columnar() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a_array = Int64Array::from(vec![Some(10), None]);
+        let b_array = Int64Array::from(vec![Some(5), Some(15)]);
+        let input_batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        let method_name = "add_columns";
+        let output_field = Field::new("output", DataType::Int64, true);
+
+        let result = register_udfs!(&input_batch, method_name, &output_field => {
+            "add_numbers" => add_numbers: (i64, i64) -> i64,
+            "add_columns" => add_columns: columnar,
+            "string_reverse" => string_reverse: (String) -> String
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 15);
+        assert!(output_array.is_null(1));
+    }
+
+    #[test]
+    fn test_register_udfs_variadic() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Utf8, true),
+        ]));
+        let a_array = StringArray::from(vec![Some("x"), Some("x")]);
+        let b_array = StringArray::from(vec![Some("y"), None]);
+        let c_array = StringArray::from(vec![Some("z"), Some("z")]);
+        let input_batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(a_array), Arc::new(b_array), Arc::new(c_array)],
+        )
+        .unwrap();
+
+        let output_field = Field::new("output", DataType::Utf8, true);
+        let result = register_udfs!(&input_batch, "join_all", &output_field => {
+            "coalesce_all" => coalesce_all: [Option<i64>] -> Option<i64>,
+            "join_all" => join_all: [&str] -> String,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "x-y-z");
+        assert!(output_array.is_null(1));
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a_array = Int64Array::from(vec![None, Some(1)]);
+        let b_array = Int64Array::from(vec![Some(2), Some(3)]);
+        let input_batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        let output_field = Field::new("output", DataType::Int64, true);
+        let result = register_udfs!(&input_batch, "coalesce_all", &output_field => {
+            "coalesce_all" => coalesce_all: [Option<i64>] -> Option<i64>,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 2);
+        assert_eq!(output_array.value(1), 1);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_register_udfs_parallel() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from_iter_values((0..1000).map(|n| n.to_string()));
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let output_field = Field::new("output", DataType::Utf8, true);
+        let result = register_udfs!(&input_batch, "string_reverse", &output_field => {
+            #[parallel(100)]
+            #[deterministic]
+            "string_reverse" => string_reverse: (String) -> String,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.len(), 1000);
+        assert_eq!(output_array.value(123), "321");
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a_array = Int64Array::from_iter_values(0..1000);
+        let b_array = Int64Array::from_iter_values(0..1000);
+        let input_batch =
+            RecordBatch::try_new(schema, vec![Arc::new(a_array), Arc::new(b_array)]).unwrap();
+
+        let output_field = Field::new("output", DataType::Int64, true);
+        let result = register_udfs!(&input_batch, "add_numbers", &output_field => {
+            #[parallel(100)]
+            "add_numbers" => add_numbers: (i64, i64) -> i64,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.len(), 1000);
+        assert_eq!(output_array.value(123), 246);
+    }
+
+    #[tokio::test]
+    async fn test_register_udfs_async() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, true)]));
+        let input_array = Int64Array::from(vec![Some(1), None, Some(3)]);
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let output_field = Field::new("output", DataType::Utf8, true);
+        let result = register_udfs!(&input_batch, "lookup_name", &output_field => {
+            "string_reverse" => string_reverse: (String) -> String,
+            #[with_concurrency(2)]
+            "lookup_name" => lookup_name: async (i64) -> String,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "user-1");
+        assert!(output_array.is_null(1));
+        assert_eq!(output_array.value(2), "user-3");
+    }
+
+    #[test]
+    fn test_register_udfs_with_context() {
+        use crate::{Identity, UdfContext};
+
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("query"), None]);
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let context = UdfContext {
+            identity: Identity {
+                id: None,
+                principal: Some("analyst".to_string()),
+                account: Some("123456789012".to_string()),
+                arn: None,
+            },
+            method_name: "tag_principal".to_string(),
+            function_type: "SCALAR".to_string(),
+            a_id: "a1".to_string(),
+            lambda: lambda_runtime::Context::default(),
+        };
+        let output_field = Field::new("output", DataType::Utf8, true);
+
+        let result = register_udfs!(
+            &input_batch, context.method_name.as_str(), &output_field, &context => {
+                "string_reverse" => string_reverse: (String) -> String,
+                "tag_principal" => tag_principal: (&UdfContext, &str) -> String,
+            }
+        );
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "query@analyst");
+        assert!(output_array.is_null(1));
+    }
+
+    #[test]
+    fn test_register_udfs_with_state() {
+        use crate::{Identity, State, UdfContext};
+
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("tmp_orders"), Some("orders"), None]);
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let context = UdfContext {
+            identity: Identity {
+                id: None,
+                principal: None,
+                account: None,
+                arn: None,
+            },
+            method_name: "has_prefix".to_string(),
+            function_type: "SCALAR".to_string(),
+            a_id: "a1".to_string(),
+            lambda: lambda_runtime::Context::default(),
+        };
+        let state = State::new(vec!["tmp_".to_string()]);
+        let output_field = Field::new("output", DataType::Boolean, true);
+
+        let result = register_udfs!(
+            &input_batch, context.method_name.as_str(), &output_field, &context, &state => {
+                "string_reverse" => string_reverse: (String) -> String,
+                "has_prefix" => has_prefix: (&State<Vec<String>>, &str) -> bool,
+            }
+        );
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<arrow::array::BooleanArray>()
+            .unwrap();
+
+        assert!(output_array.value(0));
+        assert!(!output_array.value(1));
+        assert!(output_array.is_null(2));
+    }
+
+    #[tokio::test]
+    async fn test_athena_udf_handler_state_error_fails_ping() {
+        mod inner {
+            struct Model;
+
+            fn load_model() -> Result<Model, String> {
+                Err("model.bin not found".to_string())
+            }
+
+            fn predict(_model: &crate::State<Model>, x: f64) -> f64 {
+                x
+            }
+
+            athena_udf_handler! {
+                state: Model = load_model;
+                "predict" => predict: (&State<Model>, f64) -> f64,
+            }
+
+            pub async fn ping() -> Result<crate::Value, lambda_runtime::Error> {
+                assert!(init_state().is_err());
+                let payload = serde_json::json!({
+                    "@type": "PingRequest",
+                    "identity": {},
+                    "catalogName": "lambda",
+                    "queryId": "q1"
+                });
+                function_handler(crate::LambdaEvent::new(
+                    payload,
+                    lambda_runtime::Context::default(),
+                ))
+                .await
+            }
+        }
+
+        let error = inner::ping().await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to initialize UDF state: model.bin not found"
+        );
+    }
+
+    #[test]
+    fn test_register_udfs_memoized() {
+        let schema = Arc::new(Schema::new(vec![Field::new("input", DataType::Utf8, true)]));
+        let input_array = StringArray::from(vec![Some("abc"), Some("abc"), None, Some("xy")]);
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let output_field = Field::new("output", DataType::Utf8, true);
+        let result = register_udfs!(&input_batch, "string_reverse", &output_field => {
+            #[with_memoization(crate::Memoization::Lru(100))]
+            "string_reverse" => string_reverse: (String) -> String,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), "cba");
+        assert_eq!(output_array.value(1), "cba");
+        assert!(output_array.is_null(2));
+        assert_eq!(output_array.value(3), "yx");
+    }
+
+    #[test]
+    fn test_register_udfs_null_handling() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a = Int64Array::from(vec![Some(1), None]);
+        let b = Int64Array::from(vec![None, Some(2)]);
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(a), Arc::new(b)]).unwrap();
+
+        let output_field = Field::new("output", DataType::Int64, true);
+        let result = register_udfs!(&input_batch, "coalesce_all", &output_field => {
+            #[with_null_handling(crate::NullHandling::CalledOnNullInput)]
+            "coalesce_all" => coalesce_all: [Option<i64>] -> Option<i64>,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(output_array.value(0), 1);
+        assert_eq!(output_array.value(1), 2);
+
+        let result = register_udfs!(&input_batch, "add_numbers", &output_field => {
+            #[with_null_handling(crate::NullHandling::CalledOnNullInput)]
+            "add_numbers" => add_numbers: (i64, i64) -> i64,
+        });
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "add_numbers is called on null input, but argument 1 is not an Option"
+        );
+    }
+
+    #[test]
+    fn test_register_udfs_conforms_to_output_field() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let a = Int64Array::from(vec![Some(1), None]);
+        let b = Int64Array::from(vec![Some(2), Some(3)]);
+        let input_batch = RecordBatch::try_new(schema, vec![Arc::new(a), Arc::new(b)]).unwrap();
+
+        let output_field = Field::new("output", DataType::Int64, true).with_metadata(
+            [("comment".to_string(), "sum".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        let output_batch = register_udfs!(&input_batch, "add_numbers", &output_field => {
+            "add_numbers" => add_numbers: (i64, i64) -> i64,
+        })
+        .unwrap();
+        assert_eq!(output_batch.schema().field(0), &output_field);
+
+        let output_field = Field::new("output", DataType::Int64, false);
+        let result = register_udfs!(&input_batch, "add_numbers", &output_field => {
+            "add_numbers" => add_numbers: (i64, i64) -> i64,
+        });
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "add_numbers returned NULL at row 1 but the output field output is not nullable"
+        );
+    }
+
+    #[test]
+    fn test_register_udfs_rejects_mismatched_signature() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("s", DataType::Utf8, true),
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]));
+        let s = StringArray::from(vec![Some("hello")]);
+        let a = Int64Array::from(vec![Some(1)]);
+        let b = Int64Array::from(vec![Some(2)]);
+        let input_batch =
+            RecordBatch::try_new(schema, vec![Arc::new(s), Arc::new(a), Arc::new(b)]).unwrap();
+
+        let output_field = Field::new("output", DataType::Utf8, true);
+        let result = register_udfs!(&input_batch, "string_reverse", &output_field => {
+            "string_reverse" => string_reverse: (String) -> String,
+        });
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "string_reverse expects (VARCHAR) but got (VARCHAR, BIGINT, BIGINT)"
+        );
+    }
+
+    #[test]
+    fn test_register_udfs_twelve_arguments() {
+        let mut fields = (0..11)
+            .map(|i| Field::new(format!("c{}", i), DataType::Int64, true))
+            .collect::<Vec<_>>();
+        fields.push(Field::new("weight", DataType::Float64, true));
+        let mut columns = (0..11)
+            .map(|i| Arc::new(Int64Array::from(vec![Some(i)])) as arrow::array::ArrayRef)
+            .collect::<Vec<_>>();
+        columns.push(Arc::new(arrow::array::Float64Array::from(vec![Some(0.5)])));
+        let input_batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap();
+
+        let output_field = Field::new("output", DataType::Float64, true);
+        let result = register_udfs!(&input_batch, "score", &output_field => {
+            "score" => score: (i64, i64, i64, i64, i64, i64, i64, i64, i64, i64, i64, f64) -> f64,
+        });
+
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value(0), 27.5);
+    }
+
     // Tests for athena_udf_handler! macro
     // Note: These are compile-time tests, ensuring the macro generates valid code
 
@@ -324,8 +1246,6 @@ mod tests {
         // This test verifies the macro generates valid code that compiles
         #[allow(dead_code)]
         mod inner {
-            use super::*;
-
             fn test_unary(s: String) -> String {
                 s.to_uppercase()
             }
@@ -334,10 +1254,33 @@ mod tests {
                 a + b
             }
 
+            fn test_columnar(
+                columns: &[arrow::array::ArrayRef],
+            ) -> Result<arrow::array::ArrayRef, arrow::error::ArrowError> {
+                Ok(columns[0].clone())
+            }
+
+            fn test_variadic(values: &[i64]) -> i64 {
+                values.iter().sum()
+            }
+
+            fn test_context(context: &crate::UdfContext, s: String) -> String {
+                format!("{}: {}", context.lambda.request_id, s)
+            }
+
+            async fn test_async(a: String, b: i64) -> String {
+                format!("{}{}", a, b)
+            }
+
             // This generates a function_handler
             athena_udf_handler! {
                 "test_unary" => test_unary: (String) -> String,
+                #[with_coercion(crate::Coercion::Lenient)]
                 "test_binary" => test_binary: (i64, i64) -> i64,
+                "test_columnar" => test_columnar: columnar,
+                "test_variadic" => test_variadic: [i64] -> i64,
+                "test_context" => test_context: (&UdfContext, String) -> String,
+                "test_async" => test_async: async (String, i64) -> String,
             }
         }
     }
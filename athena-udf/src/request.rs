@@ -1,9 +1,14 @@
+use crate::context::UdfContext;
 use arrow::array::RecordBatch;
-use arrow::datatypes::Schema;
+use arrow::datatypes::{Field, Schema};
 use arrow::ipc::reader::StreamReader;
-use lambda_runtime::Error;
+use futures_util::future::FutureExt;
+use lambda_runtime::{Context, Error};
 use serde::Deserialize;
+use std::any::Any;
+use std::future::Future;
 use std::io::Cursor;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::Arc;
 
 /// Represents a ping request from AWS Athena to verify Lambda function connectivity.
@@ -42,7 +47,7 @@ pub struct AthenaUDFRequest {
 ///
 /// This includes AWS account details, ARN, and other identifying information
 /// that can be used for authorization and auditing purposes.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Identity {
     #[serde(default)]
@@ -151,26 +156,137 @@ impl AthenaUDFRequest {
     /// - Output schema cannot be read
     /// - The processor function returns an error for any batch
     /// - The response cannot be constructed from the output batches
+    #[deprecated(note = "use `process_with_field`, which passes the requested output field")]
     pub fn process_with<F>(self, mut processor: F) -> Result<crate::response::AthenaResponse, Error>
     where
         F: FnMut(&RecordBatch, &str, &str) -> Result<RecordBatch, Error>,
+    {
+        self.process_with_field(|batch, method_name, output_field| {
+            processor(batch, method_name, output_field.name())
+        })
+    }
+
+    /// Processes the UDF request like [`AthenaUDFRequest::process_with`], passing the
+    /// processor the output field requested by Athena instead of its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `processor` - A function that takes a `RecordBatch`, method name, and the
+    ///   requested output field, and returns a transformed `RecordBatch`
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`AthenaUDFRequest::process_with`].
+    pub fn process_with_field<F>(
+        self,
+        mut processor: F,
+    ) -> Result<crate::response::AthenaResponse, Error>
+    where
+        F: FnMut(&RecordBatch, &str, &Field) -> Result<RecordBatch, Error>,
+    {
+        self.process_with_context(Context::default(), |batch, context, output_field| {
+            processor(batch, &context.method_name, output_field)
+        })
+    }
+
+    /// Processes the UDF request like [`AthenaUDFRequest::process_with`], passing the
+    /// processor a [`UdfContext`] instead of the method name.
+    ///
+    /// # Arguments
+    ///
+    /// * `lambda_context` - The context of the Lambda invocation carrying the request
+    /// * `processor` - A function that takes a `RecordBatch`, the request context, and
+    ///   the requested output field, and returns a transformed `RecordBatch`
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`AthenaUDFRequest::process_with`].
+    pub fn process_with_context<F>(
+        self,
+        lambda_context: Context,
+        mut processor: F,
+    ) -> Result<crate::response::AthenaResponse, Error>
+    where
+        F: FnMut(&RecordBatch, &UdfContext, &Field) -> Result<RecordBatch, Error>,
     {
         let input_batches = self.read_input_batches()?;
         let output_schema = self.read_output_schema()?;
-        let output_col_name = output_schema.field(0).name();
+        let output_field = output_schema.field(0);
+        let context = UdfContext::from_request(&self, lambda_context);
 
         let output_batches: Result<Vec<RecordBatch>, Error> = input_batches
             .iter()
-            .map(|batch| processor(batch, &self.method_name, output_col_name))
+            .map(|batch| {
+                catch_unwind(AssertUnwindSafe(|| {
+                    processor(batch, &context, output_field)
+                }))
+                .unwrap_or_else(|payload| Err(panic_error(&context, payload)))
+            })
             .collect();
 
+        self.into_response(&output_schema, output_batches?)
+    }
+
+    /// Processes the UDF request with an async processor function.
+    ///
+    /// Works like [`AthenaUDFRequest::process_with_context`], but awaits the future
+    /// returned by the processor for each input batch. The processor receives the
+    /// batch, the request context and the requested output field by value, so
+    /// the future can own them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`AthenaUDFRequest::process_with`].
+    pub async fn process_with_async<F, Fut>(
+        self,
+        lambda_context: Context,
+        processor: F,
+    ) -> Result<crate::response::AthenaResponse, Error>
+    where
+        F: Fn(RecordBatch, UdfContext, Field) -> Fut,
+        Fut: Future<Output = Result<RecordBatch, Error>>,
+    {
+        let input_batches = self.read_input_batches()?;
+        let output_schema = self.read_output_schema()?;
+        let output_field = output_schema.field(0);
+        let context = UdfContext::from_request(&self, lambda_context);
+
+        let mut output_batches = Vec::with_capacity(input_batches.len());
+        for batch in input_batches {
+            let output_batch =
+                AssertUnwindSafe(processor(batch, context.clone(), output_field.clone()))
+                    .catch_unwind()
+                    .await
+                    .unwrap_or_else(|payload| Err(panic_error(&context, payload)))?;
+            output_batches.push(output_batch);
+        }
+
+        self.into_response(&output_schema, output_batches)
+    }
+
+    /// Builds the response carrying the output batches of this request.
+    fn into_response(
+        self,
+        output_schema: &Arc<Schema>,
+        output_batches: Vec<RecordBatch>,
+    ) -> Result<crate::response::AthenaResponse, Error> {
         let response = crate::response::AthenaUDFResponse::from_batches(
-            self.method_name.clone(),
-            self.input_records.a_id.clone(),
-            &output_schema,
-            output_batches?,
+            self.method_name,
+            self.input_records.a_id,
+            output_schema,
+            output_batches,
         )?;
 
         Ok(crate::response::AthenaResponse::UserDefinedFunctionResponse(response))
     }
 }
+
+/// Describes a panic that escaped the processing of a batch.
+fn panic_error(context: &UdfContext, payload: Box<dyn Any + Send>) -> Error {
+    format!(
+        "{} panicked: {}",
+        context.method_name,
+        crate::fallible::panic_message(payload.as_ref())
+    )
+    .into()
+}
@@ -328,6 +328,7 @@ impl AthenaResponse {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use crate::request::Identity;
@@ -0,0 +1,288 @@
+use crate::arrow_conversions::FromArrowRef;
+use crate::coercion::Coercion;
+use arrow::array::{new_empty_array, Array};
+use arrow::datatypes::{DataType, FieldRef, Schema};
+use std::sync::Arc;
+
+/// Renders an Arrow type as the Athena SQL type it carries.
+///
+/// Arrow layouts that Athena does not distinguish, such as `Utf8` and
+/// `LargeUtf8`, map to the same SQL type. Types without an Athena counterpart
+/// are rendered as the Arrow type.
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow::datatypes::{DataType, Field};
+/// # use athena_udf::signature::sql_type;
+/// assert_eq!(sql_type(&DataType::Utf8View), "VARCHAR");
+/// assert_eq!(sql_type(&DataType::Decimal128(10, 2)), "DECIMAL(10,2)");
+///
+/// let list = DataType::List(Arc::new(Field::new_list_field(DataType::Int64, true)));
+/// assert_eq!(sql_type(&list), "ARRAY<BIGINT>");
+/// ```
+pub fn sql_type(data_type: &DataType) -> String {
+    render(data_type, false)
+}
+
+/// Renders an Arrow type as [`sql_type`] does, writing decimals as a bare
+/// `DECIMAL` if `any_decimal` is set.
+fn render(data_type: &DataType, any_decimal: bool) -> String {
+    match data_type {
+        DataType::Boolean => "BOOLEAN".to_string(),
+        DataType::Int8 => "TINYINT".to_string(),
+        DataType::Int16 => "SMALLINT".to_string(),
+        DataType::Int32 => "INTEGER".to_string(),
+        DataType::Int64 => "BIGINT".to_string(),
+        DataType::Float32 => "REAL".to_string(),
+        DataType::Float64 => "DOUBLE".to_string(),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "VARCHAR".to_string(),
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => "VARBINARY".to_string(),
+        DataType::Decimal128(_, _) | DataType::Decimal256(_, _) if any_decimal => {
+            "DECIMAL".to_string()
+        }
+        DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale) => {
+            format!("DECIMAL({},{})", precision, scale)
+        }
+        DataType::Date32 | DataType::Date64 => "DATE".to_string(),
+        DataType::Timestamp(_, _) => "TIMESTAMP".to_string(),
+        DataType::List(field) | DataType::LargeList(field) | DataType::FixedSizeList(field, _) => {
+            format!("ARRAY<{}>", render(field.data_type(), any_decimal))
+        }
+        DataType::Map(entries, _) => match entries.data_type() {
+            DataType::Struct(fields) if fields.len() == 2 => format!(
+                "MAP<{},{}>",
+                render(fields[0].data_type(), any_decimal),
+                render(fields[1].data_type(), any_decimal)
+            ),
+            _ => data_type.to_string(),
+        },
+        DataType::Struct(fields) => {
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    format!(
+                        "{} {}",
+                        field.name(),
+                        render(field.data_type(), any_decimal)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("ROW({})", fields.join(", "))
+        }
+        DataType::Dictionary(_, values) => render(values, any_decimal),
+        other => other.to_string(),
+    }
+}
+
+/// Replaces every decimal type within `data_type` by one of the smallest
+/// precision, to probe whether a parameter reads decimals of any precision and
+/// scale.
+fn narrow_decimals(data_type: &DataType) -> DataType {
+    let narrow_field = |field: &FieldRef| {
+        Arc::new(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(narrow_decimals(field.data_type())),
+        )
+    };
+    match data_type {
+        DataType::Decimal128(_, _) => DataType::Decimal128(1, 0),
+        DataType::Decimal256(_, _) => DataType::Decimal256(1, 0),
+        DataType::List(field) => DataType::List(narrow_field(field)),
+        DataType::LargeList(field) => DataType::LargeList(narrow_field(field)),
+        DataType::FixedSizeList(field, size) => DataType::FixedSizeList(narrow_field(field), *size),
+        DataType::Map(entries, sorted) => DataType::Map(narrow_field(entries), *sorted),
+        DataType::Struct(fields) => DataType::Struct(fields.iter().map(narrow_field).collect()),
+        DataType::Dictionary(keys, values) => {
+            DataType::Dictionary(keys.clone(), Box::new(narrow_decimals(values)))
+        }
+        other => other.clone(),
+    }
+}
+
+/// A parameter of a UDF signature.
+pub(crate) struct Parameter {
+    /// The Arrow type the parameter reads.
+    data_type: DataType,
+    /// Whether the parameter reads an array without a cast.
+    reads: fn(&dyn Array) -> bool,
+}
+
+impl Parameter {
+    pub(crate) fn of<T: FromArrowRef>() -> Self {
+        Self {
+            data_type: T::ref_array_type(),
+            reads: |array| T::ref_column(array).is_ok(),
+        }
+    }
+
+    /// Renders the parameter as an Athena SQL type, as a bare `DECIMAL` where it
+    /// reads decimals of any precision and scale.
+    fn sql_type(&self) -> String {
+        let any_decimal = (self.reads)(new_empty_array(&narrow_decimals(&self.data_type)).as_ref());
+        render(&self.data_type, any_decimal)
+    }
+
+    /// Whether a column of `data_type` can be passed to this parameter, after
+    /// decoding a dictionary and casting as `coercion` allows.
+    fn accepts(&self, data_type: &DataType, coercion: Coercion) -> bool {
+        let data_type = match data_type {
+            DataType::Dictionary(_, values) => values.as_ref(),
+            other => other,
+        };
+        (self.reads)(new_empty_array(data_type).as_ref())
+            || coercion.allows(data_type, &self.data_type)
+    }
+}
+
+/// Checks the input columns of a batch against the parameters of a UDF.
+///
+/// A `variadic` signature has a single parameter that every column is passed to.
+///
+/// # Errors
+///
+/// Returns an error listing the expected and actual SQL types if the number of
+/// columns differs or a column cannot be passed to its parameter.
+pub(crate) fn check_inputs(
+    method_name: &str,
+    parameters: &[Parameter],
+    variadic: bool,
+    schema: &Schema,
+    coercion: Coercion,
+) -> Result<(), String> {
+    let types = schema.fields().iter().map(|field| field.data_type());
+    let matches = if variadic {
+        types.clone().all(|data_type| {
+            parameters
+                .iter()
+                .all(|parameter| parameter.accepts(data_type, coercion))
+        })
+    } else {
+        types.len() == parameters.len()
+            && types
+                .clone()
+                .zip(parameters)
+                .all(|(data_type, parameter)| parameter.accepts(data_type, coercion))
+    };
+    if matches {
+        return Ok(());
+    }
+
+    let mut expected = parameters
+        .iter()
+        .map(Parameter::sql_type)
+        .collect::<Vec<_>>();
+    if variadic {
+        expected.push("...".to_string());
+    }
+    let actual = types.map(sql_type).collect::<Vec<_>>();
+    Err(format!(
+        "{} expects ({}) but got ({})",
+        method_name,
+        expected.join(", "),
+        actual.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{Field, Fields};
+    use std::sync::Arc;
+
+    fn schema(types: &[DataType]) -> Schema {
+        Schema::new(
+            types
+                .iter()
+                .enumerate()
+                .map(|(i, data_type)| Field::new(format!("c{}", i), data_type.clone(), true))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_sql_type_of_nested_types() {
+        let row = DataType::Struct(Fields::from(vec![
+            Field::new("street", DataType::Utf8, true),
+            Field::new("zip", DataType::Int32, true),
+        ]));
+        let map = DataType::Map(
+            Arc::new(Field::new(
+                "entries",
+                DataType::Struct(Fields::from(vec![
+                    Field::new("key", DataType::Utf8, false),
+                    Field::new("value", row, true),
+                ])),
+                false,
+            )),
+            false,
+        );
+
+        assert_eq!(
+            sql_type(&map),
+            "MAP<VARCHAR,ROW(street VARCHAR, zip INTEGER)>"
+        );
+    }
+
+    #[test]
+    fn test_check_inputs_lists_expected_and_actual_types() {
+        let parameters = [Parameter::of::<String>()];
+        let actual = schema(&[DataType::Utf8, DataType::Int64, DataType::Int64]);
+
+        assert_eq!(
+            check_inputs(
+                "string_reverse",
+                &parameters,
+                false,
+                &actual,
+                Coercion::Strict
+            )
+            .unwrap_err(),
+            "string_reverse expects (VARCHAR) but got (VARCHAR, BIGINT, BIGINT)"
+        );
+    }
+
+    #[test]
+    fn test_check_inputs_accepts_readable_and_coercible_columns() {
+        let parameters = [Parameter::of::<&str>(), Parameter::of::<i64>()];
+        let dictionary = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let actual = schema(&[dictionary, DataType::Int32]);
+        assert!(check_inputs("f", &parameters, false, &actual, Coercion::Strict).is_ok());
+
+        let parameters = [Parameter::of::<i32>()];
+        let actual = schema(&[DataType::Int64]);
+        assert!(check_inputs("f", &parameters, false, &actual, Coercion::Strict).is_err());
+        assert!(check_inputs("f", &parameters, false, &actual, Coercion::Lenient).is_ok());
+    }
+
+    #[test]
+    fn test_check_inputs_variadic() {
+        let parameters = [Parameter::of::<i64>()];
+        let actual = schema(&[DataType::Int64, DataType::Utf8]);
+
+        assert_eq!(
+            check_inputs("coalesce_all", &parameters, true, &actual, Coercion::Strict).unwrap_err(),
+            "coalesce_all expects (BIGINT, ...) but got (BIGINT, VARCHAR)"
+        );
+    }
+
+    #[test]
+    fn test_check_inputs_renders_decimal_parameters_without_scale() {
+        let parameters = [
+            Parameter::of::<crate::Decimal>(),
+            Parameter::of::<Vec<crate::Decimal>>(),
+        ];
+        let actual = schema(&[DataType::Decimal128(10, 2), DataType::Utf8]);
+
+        assert_eq!(
+            check_inputs("round_all", &parameters, false, &actual, Coercion::Strict).unwrap_err(),
+            "round_all expects (DECIMAL, ARRAY<DECIMAL>) but got (DECIMAL(10,2), VARCHAR)"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use lambda_runtime::Error;
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+/// State shared by the UDFs of a Lambda function, such as compiled regexes,
+/// lookup tables or loaded models.
+///
+/// A UDF whose first parameter is `&State<S>` receives the state in addition to
+/// its input values; the state is not part of the signature seen by Athena.
+/// `State` dereferences to the wrapped value.
+///
+/// # Examples
+///
+/// ```
+/// use athena_udf::State;
+///
+/// struct Blocklist {
+///     domains: Vec<String>,
+/// }
+///
+/// fn is_blocked(blocklist: &State<Blocklist>, domain: String) -> bool {
+///     blocklist.domains.contains(&domain)
+/// }
+///
+/// let blocklist = State::new(Blocklist {
+///     domains: vec!["example.com".to_string()],
+/// });
+/// assert!(is_blocked(&blocklist, "example.com".to_string()));
+///
+/// // Registered like any other UDF:
+/// //
+/// // athena_udf_handler! {
+/// //     state: Blocklist = Blocklist::load;
+/// //     "is_blocked" => is_blocked: (&State<Blocklist>, String) -> bool,
+/// // }
+/// ```
+#[derive(Debug)]
+pub struct State<S>(S);
+
+impl<S> State<S> {
+    /// Wraps an initialized state value.
+    pub fn new(state: S) -> Self {
+        Self(state)
+    }
+
+    /// Returns the wrapped state value.
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<S> Deref for State<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.0
+    }
+}
+
+/// A [`State`] that is initialized on first use and then kept for the lifetime
+/// of the Lambda container.
+///
+/// `athena_udf_handler!` declares one of these for its `state: S = init;` header.
+/// Handwritten handlers can keep one in a `static` and call [`get`](Self::get)
+/// at the start of every invocation, including pings, so that Athena sees
+/// initialization errors when it checks the function.
+///
+/// If the initialization fails, the error is returned and the next call tries
+/// again.
+///
+/// # Examples
+///
+/// ```
+/// use athena_udf::LazyState;
+///
+/// fn load_prefixes() -> Result<Vec<String>, std::io::Error> {
+///     Ok(vec!["tmp_".to_string()])
+/// }
+///
+/// static PREFIXES: LazyState<Vec<String>> =
+///     LazyState::new(|| load_prefixes().map_err(Into::into));
+///
+/// let prefixes = PREFIXES.get().unwrap();
+/// assert_eq!(prefixes.len(), 1);
+/// ```
+pub struct LazyState<S> {
+    cell: OnceLock<State<S>>,
+    init: fn() -> Result<S, Error>,
+}
+
+impl<S> LazyState<S> {
+    /// Creates a state that is initialized by `init` on first use.
+    pub const fn new(init: fn() -> Result<S, Error>) -> Self {
+        Self {
+            cell: OnceLock::new(),
+            init,
+        }
+    }
+
+    /// Returns the state, initializing it if this is the first successful call.
+    pub fn get(&self) -> Result<&State<S>, Error> {
+        if let Some(state) = self.cell.get() {
+            return Ok(state);
+        }
+
+        let state = (self.init)().map_err(|e| format!("Failed to initialize UDF state: {}", e))?;
+        Ok(self.cell.get_or_init(|| State(state)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_lazy_state_initializes_once() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static STATE: LazyState<usize> =
+            LazyState::new(|| Ok(CALLS.fetch_add(1, Ordering::SeqCst)));
+
+        assert_eq!(**STATE.get().unwrap(), 0);
+        assert_eq!(**STATE.get().unwrap(), 0);
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_lazy_state_retries_failed_initialization() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static STATE: LazyState<&str> = LazyState::new(|| {
+            if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err("model not found".into())
+            } else {
+                Ok("model")
+            }
+        });
+
+        let error = STATE.get().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to initialize UDF state: model not found"
+        );
+        assert_eq!(**STATE.get().unwrap(), "model");
+    }
+}
@@ -0,0 +1,458 @@
+use crate::arrow_conversions::{ArrowOutputBuilder, FromArrow, OutputBuilder, ToArrow};
+use arrow::array::{
+    Array, ArrayRef, AsArray, Date32Array, Date32Builder, Date64Array, Int64Array, Int64Builder,
+    PrimitiveArray,
+};
+use arrow::buffer::NullBuffer;
+use arrow::datatypes::{
+    ArrowTimestampType, DataType, Date32Type, TimeUnit, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType,
+};
+use arrow::error::ArrowError;
+use arrow::temporal_conversions::{
+    timestamp_ms_to_datetime, timestamp_ns_to_datetime, timestamp_s_to_datetime,
+    timestamp_us_to_datetime,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::sync::Arc;
+
+/// Column view over an Arrow date array of either supported width.
+pub enum DateColumn<'a> {
+    Date32(&'a Date32Array),
+    Date64(&'a Date64Array),
+}
+
+/// Column view over an Arrow timestamp array in any time unit.
+///
+/// All timestamp arrays share the same `i64` storage, so the view keeps the raw
+/// values together with the unit they are expressed in.
+pub struct TimestampColumn<'a> {
+    values: &'a [i64],
+    nulls: Option<&'a NullBuffer>,
+    unit: TimeUnit,
+}
+
+impl<'a> TimestampColumn<'a> {
+    fn try_new(array: &'a dyn Array) -> Result<Self, ArrowError> {
+        fn parts<T: ArrowTimestampType>(array: &dyn Array) -> (&[i64], Option<&NullBuffer>) {
+            let array = array.as_primitive::<T>();
+            (array.values(), array.nulls())
+        }
+
+        let DataType::Timestamp(unit, _) = array.data_type() else {
+            return Err(ArrowError::CastError(format!(
+                "expected Timestamp but got {}",
+                array.data_type()
+            )));
+        };
+        let (values, nulls) = match unit {
+            TimeUnit::Second => parts::<TimestampSecondType>(array),
+            TimeUnit::Millisecond => parts::<TimestampMillisecondType>(array),
+            TimeUnit::Microsecond => parts::<TimestampMicrosecondType>(array),
+            TimeUnit::Nanosecond => parts::<TimestampNanosecondType>(array),
+        };
+
+        Ok(Self {
+            values,
+            nulls,
+            unit: *unit,
+        })
+    }
+
+    fn value(&self, index: usize) -> Option<NaiveDateTime> {
+        if self.nulls.is_some_and(|nulls| nulls.is_null(index)) {
+            return None;
+        }
+
+        let value = self.values[index];
+        match self.unit {
+            TimeUnit::Second => timestamp_s_to_datetime(value),
+            TimeUnit::Millisecond => timestamp_ms_to_datetime(value),
+            TimeUnit::Microsecond => timestamp_us_to_datetime(value),
+            TimeUnit::Nanosecond => timestamp_ns_to_datetime(value),
+        }
+    }
+}
+
+/// Output builder for timestamps in the unit and timezone of the output type.
+///
+/// Precision below the target unit is truncated, as in an Arrow cast.
+pub struct TimestampOutputBuilder {
+    values: Int64Builder,
+    data_type: DataType,
+}
+
+impl TimestampOutputBuilder {
+    fn new(capacity: usize, data_type: Option<&DataType>) -> Result<Self, ArrowError> {
+        let data_type = data_type
+            .cloned()
+            .unwrap_or(DataType::Timestamp(TimeUnit::Millisecond, None));
+        if !matches!(data_type, DataType::Timestamp(_, _)) {
+            return Err(ArrowError::CastError(format!(
+                "Cannot write timestamp values to a {} column",
+                data_type
+            )));
+        }
+        Ok(Self {
+            values: Int64Builder::with_capacity(capacity),
+            data_type,
+        })
+    }
+}
+
+impl OutputBuilder<NaiveDateTime> for TimestampOutputBuilder {
+    fn append_value(&mut self, value: NaiveDateTime) -> Result<(), ArrowError> {
+        let DataType::Timestamp(unit, _) = &self.data_type else {
+            unreachable!("timestamp builders are created for Timestamp columns")
+        };
+        let datetime = value.and_utc();
+        let value = match unit {
+            TimeUnit::Second => Some(datetime.timestamp()),
+            TimeUnit::Millisecond => Some(datetime.timestamp_millis()),
+            TimeUnit::Microsecond => Some(datetime.timestamp_micros()),
+            TimeUnit::Nanosecond => datetime.timestamp_nanos_opt(),
+        }
+        .ok_or_else(|| {
+            ArrowError::ArithmeticOverflow(format!(
+                "Timestamp {} overflows {}",
+                datetime, self.data_type
+            ))
+        })?;
+        self.values.append_value(value);
+        Ok(())
+    }
+
+    fn append_null(&mut self) {
+        self.values.append_null();
+    }
+
+    fn finish(mut self) -> Result<ArrayRef, ArrowError> {
+        fn build<T: ArrowTimestampType>(values: &Int64Array, tz: Option<Arc<str>>) -> ArrayRef {
+            Arc::new(values.reinterpret_cast::<T>().with_timezone_opt(tz))
+        }
+
+        let values = self.values.finish();
+        let DataType::Timestamp(unit, tz) = self.data_type else {
+            unreachable!("timestamp builders are created for Timestamp columns")
+        };
+        Ok(match unit {
+            TimeUnit::Second => build::<TimestampSecondType>(&values, tz),
+            TimeUnit::Millisecond => build::<TimestampMillisecondType>(&values, tz),
+            TimeUnit::Microsecond => build::<TimestampMicrosecondType>(&values, tz),
+            TimeUnit::Nanosecond => build::<TimestampNanosecondType>(&values, tz),
+        })
+    }
+}
+
+impl OutputBuilder<DateTime<Utc>> for TimestampOutputBuilder {
+    fn append_value(&mut self, value: DateTime<Utc>) -> Result<(), ArrowError> {
+        self.append_value(value.naive_utc())
+    }
+
+    fn append_null(&mut self) {
+        self.values.append_null();
+    }
+
+    fn finish(self) -> Result<ArrayRef, ArrowError> {
+        OutputBuilder::<NaiveDateTime>::finish(self)
+    }
+}
+
+/// Converts calendar dates from Arrow Date32 or Date64 arrays.
+///
+/// Athena sends `DATE` columns as days since the Unix epoch.
+/// Returns `None` for null values and for days outside chrono's supported range.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::Date32Array;
+/// # use athena_udf::arrow_conversions::FromArrow;
+/// # use chrono::NaiveDate;
+/// let array = Date32Array::from(vec![Some(19723), None]);
+///
+/// assert_eq!(NaiveDate::from_array(&array, 0), NaiveDate::from_ymd_opt(2024, 1, 1));
+/// assert_eq!(NaiveDate::from_array(&array, 1), None);
+/// ```
+impl FromArrow for NaiveDate {
+    type Column<'a> = DateColumn<'a>;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        match array.data_type() {
+            DataType::Date32 => Ok(DateColumn::Date32(array.as_primitive())),
+            DataType::Date64 => Ok(DateColumn::Date64(array.as_primitive())),
+            other => Err(ArrowError::CastError(format!(
+                "expected Date32 but got {}",
+                other
+            ))),
+        }
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        match column {
+            DateColumn::Date32(array) if array.is_valid(index) => array.value_as_date(index),
+            DateColumn::Date64(array) if array.is_valid(index) => array.value_as_date(index),
+            _ => None,
+        }
+    }
+
+    fn array_type() -> DataType {
+        DataType::Date32
+    }
+}
+
+/// Converts calendar dates to Arrow Date32 arrays.
+///
+/// Preserves `None` values as nulls in the resulting array.
+impl ToArrow for NaiveDate {
+    type ArrayType = Date32Array;
+    type Builder = ArrowOutputBuilder<Date32Builder>;
+
+    fn data_type() -> DataType {
+        DataType::Date32
+    }
+
+    fn builder(capacity: usize, _: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        Ok(ArrowOutputBuilder(Date32Builder::with_capacity(capacity)))
+    }
+}
+
+impl OutputBuilder<NaiveDate> for ArrowOutputBuilder<Date32Builder> {
+    fn append_value(&mut self, value: NaiveDate) -> Result<(), ArrowError> {
+        self.0.append_value(Date32Type::from_naive_date(value));
+        Ok(())
+    }
+
+    fn append_null(&mut self) {
+        self.0.append_null();
+    }
+
+    fn finish(mut self) -> Result<ArrayRef, ArrowError> {
+        Ok(Arc::new(self.0.finish()))
+    }
+}
+
+/// Converts timestamps from Arrow timestamp arrays of any time unit.
+///
+/// Athena `TIMESTAMP` columns carry no time zone; values are read as UTC wall
+/// clock time. Returns `None` for null values.
+///
+/// # Examples
+///
+/// ```
+/// # use arrow::array::{TimestampMicrosecondArray, TimestampMillisecondArray};
+/// # use athena_udf::arrow_conversions::FromArrow;
+/// # use chrono::NaiveDateTime;
+/// let millis = TimestampMillisecondArray::from(vec![1_700_000_000_123]);
+/// let micros = TimestampMicrosecondArray::from(vec![1_700_000_000_123_000]);
+///
+/// assert_eq!(
+///     NaiveDateTime::from_array(&millis, 0),
+///     NaiveDateTime::from_array(&micros, 0),
+/// );
+/// ```
+impl FromArrow for NaiveDateTime {
+    type Column<'a> = TimestampColumn<'a>;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        TimestampColumn::try_new(array)
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        column.value(index)
+    }
+
+    fn array_type() -> DataType {
+        DataType::Timestamp(TimeUnit::Millisecond, None)
+    }
+}
+
+/// Converts timestamps to Arrow timestamp arrays.
+///
+/// Values are written in milliseconds unless the requested output type declares
+/// another unit or a time zone.
+impl ToArrow for NaiveDateTime {
+    type ArrayType = PrimitiveArray<TimestampMillisecondType>;
+    type Builder = TimestampOutputBuilder;
+
+    fn data_type() -> DataType {
+        DataType::Timestamp(TimeUnit::Millisecond, None)
+    }
+
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        TimestampOutputBuilder::new(capacity, data_type)
+    }
+}
+
+/// Converts timestamps from Arrow timestamp arrays of any time unit as UTC instants.
+///
+/// Returns `None` for null values.
+impl FromArrow for DateTime<Utc> {
+    type Column<'a> = TimestampColumn<'a>;
+
+    fn column(array: &dyn Array) -> Result<Self::Column<'_>, ArrowError> {
+        TimestampColumn::try_new(array)
+    }
+
+    fn from_column(column: &Self::Column<'_>, index: usize) -> Option<Self> {
+        column.value(index).map(|datetime| datetime.and_utc())
+    }
+
+    fn array_type() -> DataType {
+        DataType::Timestamp(TimeUnit::Millisecond, None)
+    }
+}
+
+/// Converts UTC instants to Arrow timestamp arrays.
+///
+/// Values are written in milliseconds unless the requested output type declares
+/// another unit or a time zone.
+impl ToArrow for DateTime<Utc> {
+    type ArrayType = PrimitiveArray<TimestampMillisecondType>;
+    type Builder = TimestampOutputBuilder;
+
+    fn data_type() -> DataType {
+        DataType::Timestamp(TimeUnit::Millisecond, None)
+    }
+
+    fn builder(capacity: usize, data_type: Option<&DataType>) -> Result<Self::Builder, ArrowError> {
+        TimestampOutputBuilder::new(capacity, data_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UDFProcessor;
+    use arrow::array::{TimestampMillisecondArray, TimestampNanosecondArray};
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use chrono::{Datelike, Days, Duration};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn datetime(year: i32, month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        date(year, month, day).and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_naive_date_from_arrow() {
+        let array = Date32Array::from(vec![Some(0), None, Some(-1)]);
+
+        assert_eq!(NaiveDate::from_array(&array, 0), Some(date(1970, 1, 1)));
+        assert_eq!(NaiveDate::from_array(&array, 1), None);
+        assert_eq!(NaiveDate::from_array(&array, 2), Some(date(1969, 12, 31)));
+    }
+
+    #[test]
+    fn test_naive_date_from_date64() {
+        let array = Date64Array::from(vec![Some(86_400_000), None]);
+
+        assert_eq!(NaiveDate::from_array(&array, 0), Some(date(1970, 1, 2)));
+        assert_eq!(NaiveDate::from_array(&array, 1), None);
+    }
+
+    #[test]
+    fn test_naive_date_to_arrow() {
+        let values = vec![Some(date(2024, 2, 29)), None];
+        let array = NaiveDate::to_array(values);
+        let date_array = array.as_any().downcast_ref::<Date32Array>().unwrap();
+
+        assert_eq!(date_array.value_as_date(0), Some(date(2024, 2, 29)));
+        assert!(date_array.is_null(1));
+    }
+
+    #[test]
+    fn test_naive_datetime_from_any_unit() {
+        let expected = datetime(2024, 1, 1, 12);
+        let millis = TimestampMillisecondArray::from(vec![Some(1_704_110_400_000), None]);
+        let nanos = TimestampNanosecondArray::from(vec![Some(1_704_110_400_000_000_000)])
+            .with_timezone("UTC");
+
+        assert_eq!(NaiveDateTime::from_array(&millis, 0), Some(expected));
+        assert_eq!(NaiveDateTime::from_array(&millis, 1), None);
+        assert_eq!(NaiveDateTime::from_array(&nanos, 0), Some(expected));
+        assert_eq!(
+            DateTime::<Utc>::from_array(&nanos, 0),
+            Some(expected.and_utc())
+        );
+    }
+
+    #[test]
+    fn test_naive_datetime_rejects_non_timestamp() {
+        let array = Date32Array::from(vec![0]);
+
+        assert!(NaiveDateTime::column(&array).is_err());
+    }
+
+    #[test]
+    fn test_naive_datetime_to_arrow_in_requested_unit() {
+        let value = datetime(2024, 1, 1, 12) + Duration::microseconds(5);
+        let data_type = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+        let array = NaiveDateTime::to_array_as(vec![Some(value), None], &data_type).unwrap();
+
+        assert_eq!(array.data_type(), &data_type);
+        let timestamps = array.as_primitive::<TimestampMicrosecondType>();
+        assert_eq!(timestamps.value(0), 1_704_110_400_000_005);
+        assert!(timestamps.is_null(1));
+    }
+
+    #[test]
+    fn test_process_unary_naive_date() {
+        let schema = Arc::new(Schema::new(vec![Field::new("d", DataType::Date32, true)]));
+        let input_array = Date32Array::from(vec![
+            Some(Date32Type::from_naive_date(date(2024, 3, 15))),
+            None,
+        ]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let processor = UDFProcessor::new(&batch);
+        let result = processor.process_unary::<NaiveDate, NaiveDate, _>("month_end", |d| {
+            date(d.year(), d.month(), 1)
+                .checked_add_months(chrono::Months::new(1))
+                .and_then(|first| first.checked_sub_days(Days::new(1)))
+                .unwrap()
+        });
+
+        assert!(result.is_ok());
+        let output_batch = result.unwrap();
+        let output_array = output_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+
+        assert_eq!(output_array.value_as_date(0), Some(date(2024, 3, 31)));
+        assert!(output_array.is_null(1));
+    }
+
+    #[test]
+    fn test_process_unary_timestamp_output_unit() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            true,
+        )]));
+        let input_array = TimestampMillisecondArray::from(vec![Some(1_704_110_400_000), None]);
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(input_array)]).unwrap();
+
+        let output_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+        let processor = UDFProcessor::new(&batch).with_output_type(output_type.clone());
+        let result = processor
+            .process_unary::<NaiveDateTime, NaiveDateTime, _>("next_hour", |ts| {
+                ts + Duration::hours(1)
+            });
+
+        assert!(result.is_ok());
+        let output_batch = result.unwrap();
+        assert_eq!(output_batch.schema().field(0).data_type(), &output_type);
+        let output_array = output_batch
+            .column(0)
+            .as_primitive::<TimestampNanosecondType>();
+
+        assert_eq!(output_array.value(0), 1_704_114_000_000_000_000);
+        assert!(output_array.is_null(1));
+    }
+}